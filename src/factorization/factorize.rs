@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use num_bigint::BigUint;
+use num_traits::{ One, Zero };
+use crate::operations::strip_small_factors_with_progress;
+use crate::primality::miller_rabin;
+use super::pollard_rho::{ pollard_rho_with_progress, MAX_ATTEMPTS };
+use super::pollard_p_minus_1::pollard_p_minus_1_with_progress;
+
+/// Trial divisors tried before switching to Pollard's rho.
+const TRIAL_LIMIT: u64 = 1000;
+
+/// Smoothness bound of the Pollard p - 1 attempt made when Pollard's rho gives up.
+const P_MINUS_1_BOUND: u64 = 100_000;
+
+/// How many iterations of a stage run between two progress reports.
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+/// Factorizes a number into its prime factors with their multiplicities.
+///
+/// Factors below 1000 are peeled off by `strip_small_factors` first. The remaining cofactor is split with
+/// `pollard_rho` and both halves are factored recursively, until `miller_rabin` reports every piece as
+/// prime. Pollard's rho finds a factor `p` in about `sqrt(p)` steps, so this handles numbers whose
+/// second largest prime factor has up to roughly 20 digits. Should Pollard's rho give up on a piece,
+/// `pollard_p_minus_1` is tried with a bound of 100000, and if that fails too the piece is finished by
+/// trial division, so no composite piece is ever reported as a factor.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to factorize.
+///
+/// # Returns
+///
+/// A vector of `(prime, exponent)` pairs sorted ascending by prime, such that the product of
+/// `prime^exponent` equals `n`. Returns an empty vector if `n` is 0 or 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::factorize;
+///
+/// let factors = factorize(&BigUint::from(360u32));
+/// assert_eq!(factors, vec![
+///     (BigUint::from(2u32), 3),
+///     (BigUint::from(3u32), 2),
+///     (BigUint::from(5u32), 1),
+/// ]);
+///
+/// // Two 10 digit primes, far beyond the reach of trial division
+/// let n = BigUint::from(9999999929u64) * BigUint::from(1000000000039u64);
+/// assert_eq!(factorize(&n), vec![
+///     (BigUint::from(9999999929u64), 1),
+///     (BigUint::from(1000000000039u64), 1),
+/// ]);
+/// ```
+pub fn factorize(n: &BigUint) -> Vec<(BigUint, u32)> {
+    factorize_with_progress(n, |_, _| {})
+}
+
+/// Factorizes a number like `factorize`, reporting progress through a callback.
+///
+/// The callback receives the name of the stage currently running and the number of iterations it has done
+/// so far: `"trial"` counts trial divisors, `"rho"` counts steps of the Pollard's rho sequence, and `"p-1"`
+/// counts the prime powers folded into the exponent of Pollard's p - 1. It is called when a stage starts,
+/// every 10000 iterations, and when the stage finishes. Pollard's rho runs once per composite piece, so
+/// its stage may be reported several times.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to factorize.
+/// * `progress` - A callback taking the stage name and the iteration count.
+///
+/// # Returns
+///
+/// The same `(prime, exponent)` pairs as `factorize`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::factorize_with_progress;
+///
+/// let mut reports = Vec::new();
+/// let n = BigUint::from(1001u64 * 1000003 * 1000033);
+/// let factors = factorize_with_progress(&n, |method, iterations| {
+///     reports.push((method.to_string(), iterations));
+/// });
+/// assert_eq!(factors.len(), 5);
+/// assert_eq!(reports.first(), Some(&("trial".to_string(), 0)));
+/// assert!(reports.iter().any(|(method, _)| method == "rho"));
+/// ```
+pub fn factorize_with_progress(n: &BigUint, mut progress: impl FnMut(&str, u64)) -> Vec<(BigUint, u32)> {
+    factorize_in_stages(n, MAX_ATTEMPTS, &mut progress)
+}
+
+/// Runs the stages of `factorize`, giving Pollard's rho at most `rho_attempts` values of `c`.
+fn factorize_in_stages(
+    n: &BigUint,
+    rho_attempts: u32,
+    progress: &mut dyn FnMut(&str, u64),
+) -> Vec<(BigUint, u32)> {
+    if n.is_zero() {
+        return Vec::new();
+    }
+
+    let (small_factors, remaining) = run_stage("trial", progress, |tick| {
+        strip_small_factors_with_progress(n, TRIAL_LIMIT, tick)
+    });
+    let mut factors: BTreeMap<BigUint, u32> = small_factors.into_iter().collect();
+    let mut pending = vec![remaining];
+    while let Some(m) = pending.pop() {
+        if m.is_one() {
+            continue;
+        }
+        if miller_rabin(&m) {
+            *factors.entry(m).or_insert(0) += 1;
+            continue;
+        }
+
+        let mut factor = run_stage("rho", progress, |tick| pollard_rho_with_progress(&m, rho_attempts, tick));
+        if factor.is_none() {
+            factor = run_stage("p-1", progress, |tick| pollard_p_minus_1_with_progress(&m, P_MINUS_1_BOUND, tick));
+        }
+        match factor {
+            Some(factor) => {
+                pending.push(&m / &factor);
+                pending.push(factor);
+            }
+            // Both Pollard methods gave up, and trial division without a limit always finishes the piece
+            None => {
+                let (trial_factors, cofactor) = run_stage("trial", progress, |tick| {
+                    strip_small_factors_with_progress(&m, u64::MAX, tick)
+                });
+                for (prime, exponent) in trial_factors {
+                    *factors.entry(prime).or_insert(0) += exponent;
+                }
+                pending.push(cofactor);
+            }
+        }
+    }
+
+    factors.into_iter().collect()
+}
+
+/// Runs one stage of the factorization, reporting its progress under `method` when it starts, every
+/// 10000 iterations, and when it finishes.
+fn run_stage<T>(
+    method: &str,
+    progress: &mut dyn FnMut(&str, u64),
+    stage: impl FnOnce(&mut dyn FnMut()) -> T,
+) -> T {
+    progress(method, 0);
+    let mut iterations = 0u64;
+    let result = stage(&mut || {
+        iterations += 1;
+        if iterations.is_multiple_of(PROGRESS_INTERVAL) {
+            progress(method, iterations);
+        }
+    });
+    progress(method, iterations);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0, 1: Empty
+        assert_eq!(factorize(&BigUint::zero()), vec![]);
+        assert_eq!(factorize(&BigUint::one()), vec![]);
+
+        // Test case prime: itself
+        assert_eq!(factorize(&BigUint::from(97u32)), vec![(BigUint::from(97u32), 1)]);
+        assert_eq!(factorize(&BigUint::from(1000000007u32)), vec![(BigUint::from(1000000007u32), 1)]);
+    }
+
+    #[test]
+    fn small_numbers() {
+        // Test case 360: 2^3 * 3^2 * 5
+        assert_eq!(
+            factorize(&BigUint::from(360u32)),
+            vec![(BigUint::from(2u32), 3), (BigUint::from(3u32), 2), (BigUint::from(5u32), 1)]
+        );
+
+        // Products of the factors give back every number up to 5000
+        for n in 1..5000u32 {
+            let product = factorize(&BigUint::from(n))
+                .iter()
+                .fold(BigUint::one(), |acc, (prime, exponent)| acc * prime.pow(*exponent));
+            assert_eq!(product, BigUint::from(n));
+        }
+    }
+
+    #[test]
+    fn large_factors() {
+        // Test case repeated large prime: 1000003^3 * 2^5
+        let p = BigUint::from(1000003u32);
+        let n = p.pow(3) * BigUint::from(32u32);
+        assert_eq!(factorize(&n), vec![(BigUint::from(2u32), 5), (p, 3)]);
+
+        // Test case three large primes
+        let primes = ["871603259", "1000000007", "193818613"];
+        let primes: Vec<BigUint> = primes
+            .iter()
+            .map(|p| BigUint::parse_bytes(p.as_bytes(), 10).unwrap())
+            .collect();
+        let n: BigUint = primes.iter().product();
+        let mut expected: Vec<(BigUint, u32)> = primes.into_iter().map(|p| (p, 1)).collect();
+        expected.sort();
+        assert_eq!(factorize(&n), expected);
+    }
+
+    #[test]
+    fn progress() {
+        // Two primes near 10^6 are left to Pollard's rho by the trial stage
+        let n = BigUint::from(999983u64 * 1000003);
+        let mut reports: Vec<(String, u64)> = Vec::new();
+        let factors = factorize_with_progress(&n, |method, iterations| {
+            reports.push((method.to_string(), iterations));
+        });
+        assert_eq!(factors, vec![(BigUint::from(999983u32), 1), (BigUint::from(1000003u32), 1)]);
+
+        assert_eq!(reports.first(), Some(&("trial".to_string(), 0)));
+        for pair in reports.windows(2) {
+            // Each stage starts from 0, and its count only grows until the next stage starts
+            let (previous, current) = (&pair[0], &pair[1]);
+            assert!(current.1 == 0 || (current.0 == previous.0 && current.1 >= previous.1));
+        }
+        assert!(reports.iter().any(|(method, _)| method == "rho"));
+    }
+
+    #[test]
+    fn rho_gives_up() {
+        // Factorizes without any rho attempts, recording the stages that ran
+        let factorize_without_rho = |n: u64| {
+            let mut methods: Vec<String> = Vec::new();
+            let factors = factorize_in_stages(&BigUint::from(n), 0, &mut |method, _| {
+                if methods.last().map(String::as_str) != Some(method) {
+                    methods.push(method.to_string());
+                }
+            });
+            (factors, methods)
+        };
+
+        // 999999937 - 1 = 2^6 * 3^2 * 13 * 83 * 1609 is smooth, so Pollard's p - 1 splits the product
+        let (factors, methods) = factorize_without_rho(999999937 * 2147483659);
+        assert_eq!(factors, vec![(BigUint::from(999999937u32), 1), (BigUint::from(2147483659u32), 1)]);
+        assert_eq!(methods, ["trial", "rho", "p-1"]);
+
+        // 1000003 - 1 = 2 * 3 * 166667 and 2147483659 - 1 = 2 * 3 * 149 * 2402107 are not, so trial
+        // division has to finish the product
+        let (factors, methods) = factorize_without_rho(1000003 * 2147483659);
+        assert_eq!(factors, vec![(BigUint::from(1000003u32), 1), (BigUint::from(2147483659u32), 1)]);
+        assert_eq!(methods, ["trial", "rho", "p-1", "trial"]);
+    }
+}
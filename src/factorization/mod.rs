@@ -0,0 +1,7 @@
+pub mod pollard_rho;
+pub mod pollard_p_minus_1;
+pub mod factorize;
+
+pub use pollard_rho::pollard_rho;
+pub use pollard_p_minus_1::pollard_p_minus_1;
+pub use factorize::{ factorize, factorize_with_progress };
@@ -0,0 +1,149 @@
+use num_bigint::BigUint;
+use num_traits::One;
+use crate::generators::get_max_primes_u64;
+use crate::operations::{ gcd, pow_mod };
+use crate::primality::miller_rabin;
+
+/// How many prime powers are folded into the exponent between two gcd checks.
+const GCD_INTERVAL: usize = 32;
+
+/// Finds a nontrivial factor of a composite number with Pollard's p - 1 algorithm.
+///
+/// For a prime factor `p` of `n`, Fermat's little theorem gives `a^k = 1 mod p` whenever `p - 1` divides
+/// `k`. Taking `k = lcm(1, ..., bound)` and `a = 2`, `gcd(a^k - 1, n)` reveals `p` as soon as `p - 1` is
+/// `bound`-smooth, that is, every prime power dividing `p - 1` is at most `bound`. The exponent is built
+/// one prime power at a time with `pow_mod`, and the gcd is checked every 32 prime powers. When every
+/// factor of `n` shows up in the same interval, the interval is replayed one prime power at a time.
+///
+/// The method only succeeds when some prime factor has a smooth `p - 1`, so it complements rather than
+/// replaces `pollard_rho`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to factor.
+/// * `bound` - A `u64` representing the smoothness bound on the prime powers of `p - 1`.
+///
+/// # Returns
+///
+/// * `Some(factor)` with `1 < factor < n` and `factor` dividing `n`, if a factor was found. The factor is not
+///   necessarily prime.
+/// * `None` if `n` is prime according to `miller_rabin`, if `n` is less than 4, or if no prime factor `p` of
+///   `n` has a `bound`-smooth `p - 1`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::pollard_p_minus_1;
+///
+/// // 999999937 - 1 = 2^6 * 3^2 * 13 * 83 * 1609, while 2147483659 - 1 has the factor 2402107
+/// let n = BigUint::from(999999937u64 * 2147483659u64);
+/// assert_eq!(pollard_p_minus_1(&n, 2000), Some(BigUint::from(999999937u64)));
+/// assert_eq!(pollard_p_minus_1(&n, 1000), None);
+/// ```
+pub fn pollard_p_minus_1(n: &BigUint, bound: u64) -> Option<BigUint> {
+    pollard_p_minus_1_with_progress(n, bound, &mut || {})
+}
+
+/// Runs `pollard_p_minus_1`, calling `tick` after every prime power folded into the exponent.
+pub(crate) fn pollard_p_minus_1_with_progress(n: &BigUint, bound: u64, tick: &mut dyn FnMut()) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) || miller_rabin(n) {
+        return None;
+    }
+    if !n.bit(0) {
+        return Some(BigUint::from(2u32));
+    }
+
+    // The largest power of each prime that stays within the bound, so their product is lcm(1, ..., bound)
+    let prime_powers: Vec<BigUint> = get_max_primes_u64(bound)
+        .into_iter()
+        .map(|p| {
+            let mut power = p;
+            while let Some(next) = power.checked_mul(p).filter(|&next| next <= bound) {
+                power = next;
+            }
+            BigUint::from(power)
+        })
+        .collect();
+
+    let mut a = BigUint::from(2u32);
+    for chunk in prime_powers.chunks(GCD_INTERVAL) {
+        let checkpoint = a.clone();
+        for power in chunk {
+            a = pow_mod(&a, power, n);
+            tick();
+        }
+
+        let d = gcd(&(&a + n - BigUint::one()), n);
+        if d.is_one() {
+            continue;
+        }
+        if d != *n {
+            return Some(d);
+        }
+
+        // Every factor appeared within this chunk, so replay it with a gcd after each prime power
+        a = checkpoint;
+        for power in chunk {
+            a = pow_mod(&a, power, n);
+            tick();
+            let d = gcd(&(&a + n - BigUint::one()), n);
+            if d == *n {
+                return None;
+            }
+            if !d.is_one() {
+                return Some(d);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0, 1, 2, 3: None
+        for n in 0..4u32 {
+            assert_eq!(pollard_p_minus_1(&BigUint::from(n), 100), None);
+        }
+
+        // Test case even: 2
+        assert_eq!(pollard_p_minus_1(&BigUint::from(1000u32), 100), Some(BigUint::from(2u32)));
+
+        // Test case prime: None
+        assert_eq!(pollard_p_minus_1(&BigUint::from(1000000007u32), 100), None);
+
+        // Test case bound 0: None
+        assert_eq!(pollard_p_minus_1(&BigUint::from(999999937u64 * 2147483659u64), 0), None);
+    }
+
+    #[test]
+    fn smooth_factor() {
+        // 999999937 - 1 = 2^6 * 3^2 * 13 * 83 * 1609 is 1609-smooth
+        // 2147483659 - 1 = 2 * 3 * 149 * 2402107 is not
+        let p = BigUint::from(999999937u64);
+        let q = BigUint::from(2147483659u64);
+        let n = &p * &q;
+        assert_eq!(pollard_p_minus_1(&n, 1609), Some(p.clone()));
+        assert_eq!(pollard_p_minus_1(&n, 1608), None);
+
+        // 1234567891 - 1 = 2 * 3^2 * 5 * 3607 * 3803, found by a larger bound from a 30 digit product
+        // whose other factor has 507526619771207 dividing its p - 1
+        let r = BigUint::from(1234567891u64);
+        let n = &r * BigUint::parse_bytes(b"100000000000000000039", 10).unwrap();
+        assert_eq!(pollard_p_minus_1(&n, 4000), Some(r));
+    }
+
+    #[test]
+    fn all_factors_in_one_interval() {
+        // Both 1009 - 1 = 2^4 * 3^2 * 7 and 1013 - 1 = 2^2 * 11 * 23 are smooth for small bounds, so the
+        // first interval closes on both and the replay must separate them
+        let n = BigUint::from(1009u32 * 1013);
+        let factor = pollard_p_minus_1(&n, 200).unwrap();
+        assert!(factor == BigUint::from(1009u32) || factor == BigUint::from(1013u32));
+    }
+}
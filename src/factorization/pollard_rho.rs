@@ -0,0 +1,122 @@
+use num_bigint::BigUint;
+use num_traits::{ One, Zero };
+use crate::operations::{ gcd, pow_mod };
+use crate::primality::miller_rabin;
+
+/// How many values of `c` `pollard_rho` tries before giving up.
+pub(crate) const MAX_ATTEMPTS: u32 = 1000;
+
+/// Finds a nontrivial factor of a composite number with Pollard's rho algorithm.
+///
+/// The sequence `x -> x^2 + c mod n` eventually cycles modulo every prime factor `p` of `n`, typically after
+/// about `sqrt(p)` steps. Floyd's cycle detection walks the sequence at two speeds, and `gcd(|x - y|, n)`
+/// reveals `p` once both walkers agree modulo `p`. When the gcd is `n` itself the cycle closed modulo every
+/// factor at once, and the search restarts with the next value of `c`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to factor.
+///
+/// # Returns
+///
+/// * `Some(factor)` with `1 < factor < n` and `factor` dividing `n`, if `n` is composite. The factor is not
+///   necessarily prime.
+/// * `None` if `n` is prime according to `miller_rabin`, if `n` is less than 4, or if no factor was found
+///   after 1000 values of `c`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::pollard_rho;
+///
+/// let n = BigUint::from(8051u32);
+/// let factor = pollard_rho(&n).unwrap();
+/// assert!(factor == BigUint::from(83u32) || factor == BigUint::from(97u32));
+///
+/// assert_eq!(pollard_rho(&BigUint::from(97u32)), None);
+/// ```
+pub fn pollard_rho(n: &BigUint) -> Option<BigUint> {
+    pollard_rho_with_progress(n, MAX_ATTEMPTS, &mut || {})
+}
+
+/// Runs `pollard_rho` with at most `attempts` values of `c`, calling `tick` after every step of the sequence.
+pub(crate) fn pollard_rho_with_progress(n: &BigUint, attempts: u32, tick: &mut dyn FnMut()) -> Option<BigUint> {
+    if *n < BigUint::from(4u32) || miller_rabin(n) {
+        return None;
+    }
+    if !n.bit(0) {
+        return Some(BigUint::from(2u32));
+    }
+
+    let two = BigUint::from(2u32);
+    for c in 1..=attempts {
+        let c = BigUint::from(c);
+        let step = |x: &BigUint| (pow_mod(x, &two, n) + &c) % n;
+
+        let mut x = two.clone();
+        let mut y = two.clone();
+        let mut d = BigUint::one();
+        while d.is_one() {
+            tick();
+            x = step(&x);
+            y = step(&step(&y));
+            let difference = if x > y { &x - &y } else { &y - &x };
+            d = gcd(&difference, n);
+        }
+
+        if d != *n && !d.is_zero() {
+            return Some(d);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0, 1, 2, 3: None
+        for n in 0..4u32 {
+            assert_eq!(pollard_rho(&BigUint::from(n)), None);
+        }
+
+        // Test case 4: 2
+        assert_eq!(pollard_rho(&BigUint::from(4u32)), Some(BigUint::from(2u32)));
+
+        // Test case 97: None
+        assert_eq!(pollard_rho(&BigUint::from(97u32)), None);
+    }
+
+    #[test]
+    fn semiprimes() {
+        let pairs = [
+            ("871603259", "98762051"),
+            ("1000000007", "999999937"),
+            ("123575321", "193818613"),
+            ("444444443", "444444443"),
+            ("9999999929", "1000000000039"),
+        ];
+
+        for (p, q) in pairs {
+            let p = BigUint::parse_bytes(p.as_bytes(), 10).unwrap();
+            let q = BigUint::parse_bytes(q.as_bytes(), 10).unwrap();
+            let n = &p * &q;
+            let factor = pollard_rho(&n).unwrap();
+            assert!(factor == p || factor == q);
+        }
+    }
+
+    #[test]
+    fn prime_powers() {
+        for (p, k) in [(3u32, 2u32), (7, 3), (101, 2), (65537, 2)] {
+            let n = BigUint::from(p).pow(k);
+            let factor = pollard_rho(&n).unwrap();
+            assert!(factor > BigUint::one() && factor < n);
+            assert!((&n % &factor).is_zero());
+        }
+    }
+}
@@ -1,3 +1,43 @@
 mod primes;
+mod random;
 
-pub use primes::get_max_primes;
\ No newline at end of file
+pub use primes::{
+    get_max_primes,
+    get_max_primes_deltas,
+    decode_prime_deltas,
+    next_prime,
+    prev_prime,
+    prime_pi,
+    prime_density,
+    sum_of_primes_up_to,
+    get_max_primes_u64,
+    linear_sieve,
+    mertens,
+    abc_triples_up_to,
+    nth_prime_upper_bound,
+    first_n_primes,
+    carmichael_numbers_up_to,
+    prime_list_checksum,
+    sieve_arithmetic_progression,
+    is_right_truncatable_prime,
+    is_left_truncatable_prime,
+    is_circular_prime,
+    nth_prime,
+    primes_with_property,
+    merge_sorted_prime_segments,
+    prime_counts_per_block,
+    get_primes_in_range,
+    primes_up_to,
+    PrimeIter,
+    first_gap_occurrences,
+    prime_run_length,
+    li,
+    prime_count_by_bitlength,
+    sophie_germain_primes_up_to,
+    twin_primes_up_to,
+    prime_gaps_up_to,
+    max_prime_gap_up_to,
+};
+#[cfg(feature = "async")]
+pub use primes::prime_stream;
+pub use random::{ random_prime, random_prime_seeded, random_safe_prime, random_composite };
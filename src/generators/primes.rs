@@ -1,9 +1,16 @@
+use std::collections::BTreeMap;
 use num_bigint::BigUint;
+use num_traits::{ One, ToPrimitive, Zero };
+use crate::primality::baillie_psw::baillie_psw;
+use crate::primality::miller_rabin;
+use crate::operations::{ gcd, is_coprime, radical };
+use crate::factorization::factorize;
 
 /// Generates all prime numbers up to a given maximum value.
 ///
 /// This function uses the Sieve of Eratosthenes algorithm to efficiently generate all prime numbers less than
 /// or equal to the specified maximum value. It is useful for tasks that require a list of small prime numbers.
+/// It collects `primes_up_to`, which yields the same primes lazily.
 ///
 /// # Arguments
 ///
@@ -25,28 +32,1341 @@ use num_bigint::BigUint;
 /// assert_eq!(primes, vec![BigUint::from(2u32), BigUint::from(3u32), BigUint::from(5u32), BigUint::from(7u32)]);
 /// ```
 pub fn get_max_primes(maximum: u64) -> Vec<BigUint> {
+    primes_up_to(maximum).collect()
+}
+
+/// A lazy iterator over the prime numbers up to a maximum value, created by `primes_up_to`.
+///
+/// The primes are produced by a segmented Sieve of Eratosthenes, one block of 65536 numbers at a time, so
+/// only the current block and the base primes up to `sqrt(maximum)` are held in memory.
+pub struct PrimeIter {
+    end: u64,
+    next_low: u64,
+    base_primes: Vec<u64>,
+    segment: std::vec::IntoIter<u64>,
+}
+
+impl Iterator for PrimeIter {
+    type Item = BigUint;
+
+    fn next(&mut self) -> Option<BigUint> {
+        loop {
+            if let Some(p) = self.segment.next() {
+                return Some(BigUint::from(p));
+            }
+            if self.next_low >= self.end {
+                return None;
+            }
+            let high = self.next_low.saturating_add(SEGMENT_SIZE).min(self.end);
+            self.segment = sieve_segment(self.next_low, high, &self.base_primes).into_iter();
+            self.next_low = high;
+        }
+    }
+}
+
+/// Lazily generates all prime numbers up to a given maximum value.
+///
+/// Unlike `get_max_primes`, the primes are produced on demand, so callers can stop early with `take` or
+/// `find` without materializing the whole list.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which prime numbers are to be generated.
+///
+/// # Returns
+///
+/// A `PrimeIter` yielding all prime numbers less than or equal to `maximum` as `BigUint`, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::primes_up_to;
+///
+/// let first = primes_up_to(1_000_000_000).find(|p| *p > BigUint::from(100u32));
+/// assert_eq!(first, Some(BigUint::from(101u32)));
+/// ```
+pub fn primes_up_to(maximum: u64) -> PrimeIter {
+    PrimeIter {
+        // u64::MAX = 2^64 - 1 is composite, so leaving it out of the range is harmless
+        end: maximum.saturating_add(1),
+        next_low: 0,
+        base_primes: get_max_primes_u64((maximum as f64).sqrt() as u64 + 1),
+        segment: Vec::new().into_iter(),
+    }
+}
+
+/// Generates all prime numbers up to a given maximum value as plain `u64` values.
+///
+/// This is the same Sieve of Eratosthenes as `get_max_primes`, without allocating a `BigUint` per prime.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which prime numbers are to be generated.
+///
+/// # Returns
+///
+/// A vector of `u64` containing all prime numbers less than or equal to `maximum`.
+/// Returns an empty vector if `maximum` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::get_max_primes_u64;
+///
+/// assert_eq!(get_max_primes_u64(10), vec![2, 3, 5, 7]);
+/// ```
+pub fn get_max_primes_u64(maximum: u64) -> Vec<u64> {
+    sieve(maximum)
+        .iter()
+        .enumerate()
+        .filter(|(_, is_prime)| **is_prime)
+        .map(|(i, _)| i as u64)
+        .collect()
+}
+
+/// Runs a linear sieve computing the primes and Euler's totient of every number up to a maximum value.
+///
+/// Unlike the Sieve of Eratosthenes, the linear sieve crosses out every composite exactly once, through
+/// its smallest prime factor, so it runs in `O(max)`. Multiplicative functions such as the totient come
+/// for free along the way: `φ(i * p) = φ(i) * p` when `p` divides `i`, and `φ(i) * (p - 1)` otherwise.
+///
+/// # Arguments
+///
+/// * `max` - A `u64` representing the maximum value of the sieve.
+///
+/// # Returns
+///
+/// A tuple `(primes, totients)` where `primes` holds all primes less than or equal to `max`, and
+/// `totients[i]` is `φ(i)` for every `0 <= i <= max` (with `φ(0) = 0`).
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::linear_sieve;
+///
+/// let (primes, totients) = linear_sieve(10);
+/// assert_eq!(primes, vec![2, 3, 5, 7]);
+/// assert_eq!(totients, vec![0, 1, 1, 2, 2, 4, 2, 6, 4, 6, 4]);
+/// ```
+pub fn linear_sieve(max: u64) -> (Vec<u64>, Vec<u64>) {
+    let n = max as usize;
+    let mut totients = vec![0u64; n + 1];
+    let mut primes: Vec<u64> = Vec::new();
+    if n >= 1 {
+        totients[1] = 1;
+    }
+
+    for i in 2..=n {
+        if totients[i] == 0 {
+            totients[i] = (i - 1) as u64;
+            primes.push(i as u64);
+        }
+        for &p in &primes {
+            let p = p as usize;
+            if i * p > n {
+                break;
+            }
+            if i % p == 0 {
+                totients[i * p] = totients[i] * (p as u64);
+                break;
+            }
+            totients[i * p] = totients[i] * ((p - 1) as u64);
+        }
+    }
+
+    (primes, totients)
+}
+
+/// Computes the Mertens function `M(n)`, the sum of the Möbius function `μ(k)` for all `1 <= k <= n`.
+///
+/// The Möbius values are produced by a linear sieve rather than by factoring each number:
+/// `μ(i * p) = -μ(i)` when the prime `p` does not divide `i`, and `0` otherwise.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing the upper bound of the sum.
+///
+/// # Returns
+///
+/// The value of `M(n)`. Returns zero if `n` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::mertens;
+///
+/// assert_eq!(mertens(10), -1);
+/// ```
+pub fn mertens(n: u64) -> i64 {
+    mobius_sieve(n)
+        .iter()
+        .map(|mu| *mu as i64)
+        .sum()
+}
+
+/// Runs a linear sieve returning the Möbius function `μ(i)` for every `0 <= i <= max` (with `μ(0) = 0`).
+fn mobius_sieve(max: u64) -> Vec<i8> {
+    let n = max as usize;
+    let mut mobius = vec![0i8; n + 1];
+    let mut is_composite = vec![false; n + 1];
+    let mut primes: Vec<usize> = Vec::new();
+    if n >= 1 {
+        mobius[1] = 1;
+    }
+
+    for i in 2..=n {
+        if !is_composite[i] {
+            mobius[i] = -1;
+            primes.push(i);
+        }
+        for &p in &primes {
+            if i * p > n {
+                break;
+            }
+            is_composite[i * p] = true;
+            if i % p == 0 {
+                mobius[i * p] = 0;
+                break;
+            }
+            mobius[i * p] = -mobius[i];
+        }
+    }
+
+    mobius
+}
+
+/// Finds abc triples up to a maximum value whose quality exceeds a threshold.
+///
+/// An abc triple is a set of coprime positive integers `a < b` with `a + b = c`. Its quality is
+/// `ln(c) / ln(rad(abc))`, and the abc conjecture states that only finitely many triples have a quality
+/// above `1 + ε` for any `ε > 0`. Since `a`, `b` and `c` are pairwise coprime, `rad(abc)` is computed as
+/// `rad(a) * rad(b) * rad(c)`.
+///
+/// # Arguments
+///
+/// * `max` - A `u64` representing the largest value allowed for `c`.
+/// * `quality_threshold` - An `f64` that the quality of a triple must exceed to be returned.
+///
+/// # Returns
+///
+/// A vector of `(a, b, c)` triples, ordered by `c` and then by `a`.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::abc_triples_up_to;
+///
+/// assert_eq!(abc_triples_up_to(10, 1.0), vec![(1, 8, 9)]);
+/// ```
+pub fn abc_triples_up_to(max: u64, quality_threshold: f64) -> Vec<(u64, u64, u64)> {
+    let radicals: Vec<f64> = (0..=max)
+        .map(|n| radical(&BigUint::from(n)).to_f64().unwrap())
+        .collect();
+
+    let mut triples: Vec<(u64, u64, u64)> = Vec::new();
+    for c in 3..=max {
+        for a in 1..=(c - 1) / 2 {
+            let b = c - a;
+            if !is_coprime(&BigUint::from(a), &BigUint::from(b)) {
+                continue;
+            }
+            let rad = radicals[a as usize] * radicals[b as usize] * radicals[c as usize];
+            let quality = (c as f64).ln() / rad.ln();
+            if quality > quality_threshold {
+                triples.push((a, b, c));
+            }
+        }
+    }
+    triples
+}
+
+/// Computes an upper bound for the nth prime.
+///
+/// For `n >= 6` the nth prime is known to be smaller than `n * (ln n + ln ln n)` (Rosser's theorem).
+/// Smaller values of `n` are covered by the constant 13, since the fifth prime is 11.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing the 1-indexed position of the prime.
+///
+/// # Returns
+///
+/// A `u64` guaranteed to be greater than or equal to the nth prime.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::nth_prime_upper_bound;
+///
+/// assert!(nth_prime_upper_bound(1000) >= 7919);
+/// ```
+pub fn nth_prime_upper_bound(n: u64) -> u64 {
+    if n < 6 {
+        return 13;
+    }
+    let n = n as f64;
+    (n * (n.ln() + n.ln().ln())).ceil() as u64
+}
+
+/// Generates exactly the first `n` prime numbers.
+///
+/// The sieve is sized with `nth_prime_upper_bound`, so a single pass is always enough.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing how many primes to generate.
+///
+/// # Returns
+///
+/// A vector of `BigUint` containing the first `n` primes in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::first_n_primes;
+///
+/// let primes = first_n_primes(4);
+/// assert_eq!(primes, vec![BigUint::from(2u32), BigUint::from(3u32), BigUint::from(5u32), BigUint::from(7u32)]);
+/// ```
+pub fn first_n_primes(n: u64) -> Vec<BigUint> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut primes = get_max_primes(nth_prime_upper_bound(n));
+    primes.truncate(n as usize);
+    primes
+}
+
+/// Generates the primes up to a given maximum value that satisfy a predicate.
+///
+/// # Arguments
+///
+/// * `max` - A `u64` representing the maximum value up to which primes are generated.
+/// * `pred` - A predicate deciding which primes are kept.
+///
+/// # Returns
+///
+/// A vector of `BigUint` containing the primes less than or equal to `max` for which `pred` returns `true`,
+/// in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::primes_with_property;
+///
+/// // Primes congruent to 3 mod 4
+/// let four = BigUint::from(4u32);
+/// let primes = primes_with_property(20, |p| p % &four == BigUint::from(3u32));
+/// assert_eq!(primes, vec![BigUint::from(3u32), BigUint::from(7u32), BigUint::from(11u32), BigUint::from(19u32)]);
+/// ```
+pub fn primes_with_property(max: u64, pred: impl Fn(&BigUint) -> bool) -> Vec<BigUint> {
+    get_max_primes(max)
+        .into_iter()
+        .filter(|p| pred(p))
+        .collect()
+}
+
+/// Generates all twin prime pairs up to a given maximum value.
+///
+/// Twin primes are pairs `(p, p + 2)` of primes. Both members of every pair are read from the same sieve
+/// as `get_max_primes`, and a pair is only included when `p + 2` is itself at most `maximum`.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value of the larger member of each pair.
+///
+/// # Returns
+///
+/// A vector of `(p, p + 2)` pairs of `BigUint` with `p + 2 <= maximum`, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::twin_primes_up_to;
+///
+/// let twins = twin_primes_up_to(20);
+/// assert_eq!(twins[0], (BigUint::from(3u32), BigUint::from(5u32)));
+/// assert_eq!(twins.len(), 4);
+/// ```
+pub fn twin_primes_up_to(maximum: u64) -> Vec<(BigUint, BigUint)> {
+    let is_prime = sieve(maximum);
+    (3..=maximum.saturating_sub(2))
+        .filter(|&p| is_prime[p as usize] && is_prime[(p + 2) as usize])
+        .map(|p| (BigUint::from(p), BigUint::from(p + 2)))
+        .collect()
+}
+
+/// Generates all Sophie Germain primes up to a given maximum value.
+///
+/// A Sophie Germain prime is a prime `p` for which `2p + 1` is also prime; `2p + 1` is then a safe prime.
+/// Both are read from a single sieve up to `2 * limit + 1`.
+///
+/// # Arguments
+///
+/// * `limit` - A `u64` representing the maximum value up to which Sophie Germain primes are generated.
+///
+/// # Returns
+///
+/// A vector of `BigUint` containing all primes `p <= limit` with `2p + 1` prime, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::sophie_germain_primes_up_to;
+///
+/// let primes: Vec<BigUint> = [2u32, 3, 5, 11, 23].iter().map(|&p| BigUint::from(p)).collect();
+/// assert_eq!(sophie_germain_primes_up_to(25), primes);
+/// ```
+pub fn sophie_germain_primes_up_to(limit: u64) -> Vec<BigUint> {
+    let is_prime = sieve(limit.saturating_mul(2).saturating_add(1));
+    (2..=limit)
+        .filter(|&p| is_prime[p as usize] && is_prime[(2 * p + 1) as usize])
+        .map(BigUint::from)
+        .collect()
+}
+
+/// Counts how many consecutive values of a polynomial are prime, starting from `n = 0`.
+///
+/// Euler's polynomial `n^2 + n + 41` is prime for every `n` from 0 to 39, the classic example of a
+/// prime-generating polynomial. Each value is tested with `miller_rabin`, and the count stops at the first
+/// value that is not prime.
+///
+/// # Arguments
+///
+/// * `poly` - A function evaluating the polynomial at `n`.
+///
+/// # Returns
+///
+/// The number of consecutive `n`, starting from 0, for which `poly(n)` is prime. Returns 0 if `poly(0)` is
+/// not prime.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::prime_run_length;
+///
+/// let euler = |n: u64| BigUint::from(n * n + n + 41);
+/// assert_eq!(prime_run_length(euler), 40);
+/// ```
+pub fn prime_run_length(poly: impl Fn(u64) -> BigUint) -> u64 {
+    let mut n = 0u64;
+    while miller_rabin(&poly(n)) {
+        n += 1;
+    }
+    n
+}
+
+/// Merges prime lists from consecutive sieve segments into one sorted list.
+///
+/// The segments must already be sorted and must not overlap, with every prime of a segment smaller than
+/// the primes of the segments after it, as produced by a segmented or parallel sieve. The merge is then a
+/// plain concatenation.
+///
+/// # Arguments
+///
+/// * `segments` - A vector of sorted, non-overlapping prime lists in ascending order of their ranges.
+///
+/// # Returns
+///
+/// A vector of `BigUint` containing every prime of every segment, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::merge_sorted_prime_segments;
+///
+/// let segments = vec![vec![BigUint::from(2u32), BigUint::from(3u32)], vec![BigUint::from(5u32)]];
+/// assert_eq!(merge_sorted_prime_segments(segments), vec![BigUint::from(2u32), BigUint::from(3u32), BigUint::from(5u32)]);
+/// ```
+pub fn merge_sorted_prime_segments(segments: Vec<Vec<BigUint>>) -> Vec<BigUint> {
+    segments.into_iter().flatten().collect()
+}
+
+/// Counts the primes in consecutive blocks of integers up to a given maximum value.
+///
+/// Block `i` covers the integers from `i * block_size + 1` to `(i + 1) * block_size`, so the blocks split
+/// `1..=max` into `ceil(max / block_size)` pieces, the last of which may be cut short by `max`.
+///
+/// # Arguments
+///
+/// * `max` - A `u64` representing the maximum value up to which primes are counted.
+/// * `block_size` - A `u64` representing the number of integers in each block.
+///
+/// # Returns
+///
+/// A vector holding the number of primes in each block, in order. It is empty if `max` or `block_size` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::prime_counts_per_block;
+///
+/// assert_eq!(prime_counts_per_block(30, 10), vec![4, 4, 2]);
+/// ```
+pub fn prime_counts_per_block(max: u64, block_size: u64) -> Vec<u64> {
+    if max == 0 || block_size == 0 {
+        return Vec::new();
+    }
+
+    let mut counts = vec![0u64; max.div_ceil(block_size) as usize];
+    for p in get_max_primes_u64(max) {
+        counts[((p - 1) / block_size) as usize] += 1;
+    }
+    counts
+}
+
+/// Counts the primes of each bit length, up to a given number of bits.
+///
+/// A prime has bit length `b` when it lies in `[2^(b-1), 2^b)`, so the counts show how many primes a key
+/// generator can pick from at each size. The primes are counted exactly with a segmented sieve over
+/// `[2, 2^max_bits)`, which keeps memory small but takes time proportional to `2^max_bits`, so this is
+/// practical up to about 32 bits.
+///
+/// # Arguments
+///
+/// * `max_bits` - A `u64` representing the largest bit length counted. It must be at most 64.
+///
+/// # Returns
+///
+/// A vector of length `max_bits + 1` whose entry `b` is the number of primes with exactly `b` bits. Entries 0
+/// and 1 are always 0.
+///
+/// # Panics
+///
+/// Panics if `max_bits` is greater than 64.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::prime_count_by_bitlength;
+///
+/// // 2, 3 | 5, 7 | 11, 13 | 17, 19, 23, 29, 31
+/// assert_eq!(prime_count_by_bitlength(5), vec![0, 0, 2, 2, 2, 5]);
+/// ```
+pub fn prime_count_by_bitlength(max_bits: u64) -> Vec<u64> {
+    assert!(max_bits <= 64, "prime_count_by_bitlength counts at most 64 bit primes");
+
+    let mut counts = vec![0u64; max_bits as usize + 1];
+    // u64::MAX = 2^64 - 1 is composite, so leaving it out of the range is harmless
+    let end = if max_bits == 64 { u64::MAX } else { 1u64 << max_bits };
+    let base_primes = get_max_primes_u64((end as f64).sqrt() as u64 + 1);
+
+    let mut low = 0u64;
+    while low < end {
+        let high = low.saturating_add(SEGMENT_SIZE).min(end);
+        for p in sieve_segment(low, high, &base_primes) {
+            counts[(64 - p.leading_zeros()) as usize] += 1;
+        }
+        low = high;
+    }
+    counts
+}
+
+/// Finds the first prime at which each gap between consecutive primes occurs, up to a given maximum value.
+///
+/// # Arguments
+///
+/// * `max` - A `u64` representing the maximum value up to which primes are generated.
+///
+/// # Returns
+///
+/// A `BTreeMap` from each gap size `g` to the first prime `p` such that the next prime is `p + g`, considering
+/// only pairs of consecutive primes that are both at most `max`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::first_gap_occurrences;
+///
+/// let gaps = first_gap_occurrences(100);
+/// assert_eq!(gaps[&8], BigUint::from(89u32));
+/// ```
+pub fn first_gap_occurrences(max: u64) -> BTreeMap<u64, BigUint> {
+    let primes = get_max_primes_u64(max);
+    let mut occurrences = BTreeMap::new();
+    for pair in primes.windows(2) {
+        occurrences.entry(pair[1] - pair[0]).or_insert_with(|| BigUint::from(pair[0]));
+    }
+    occurrences
+}
+
+/// Lists every prime up to a given maximum value together with the gap to the next prime.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which primes are generated.
+///
+/// # Returns
+///
+/// A vector of `(p, gap)` pairs in ascending order of `p`, where `p + gap` is the prime following `p`. Only
+/// primes whose next prime is also at most `maximum` are included, so the largest prime up to `maximum` is
+/// left out.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::prime_gaps_up_to;
+///
+/// let gaps = prime_gaps_up_to(11);
+/// assert_eq!(gaps, vec![
+///     (BigUint::from(2u32), 1),
+///     (BigUint::from(3u32), 2),
+///     (BigUint::from(5u32), 2),
+///     (BigUint::from(7u32), 4),
+/// ]);
+/// ```
+pub fn prime_gaps_up_to(maximum: u64) -> Vec<(BigUint, u64)> {
+    get_max_primes_u64(maximum)
+        .windows(2)
+        .map(|pair| (BigUint::from(pair[0]), pair[1] - pair[0]))
+        .collect()
+}
+
+/// Finds the largest gap between consecutive primes up to a given maximum value.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which primes are generated.
+///
+/// # Returns
+///
+/// A tuple `(p, gap)` where `p` is the first prime starting a gap of the largest size `gap`, among pairs of
+/// consecutive primes that are both at most `maximum`. Returns `(0, 0)` if there are fewer than two primes
+/// up to `maximum`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::max_prime_gap_up_to;
+///
+/// // 89 and 97 are the farthest apart consecutive primes below 100
+/// assert_eq!(max_prime_gap_up_to(100), (BigUint::from(89u32), 8));
+/// ```
+pub fn max_prime_gap_up_to(maximum: u64) -> (BigUint, u64) {
+    let mut largest = (0u64, 0u64);
+    for pair in get_max_primes_u64(maximum).windows(2) {
+        let gap = pair[1] - pair[0];
+        if gap > largest.1 {
+            largest = (pair[0], gap);
+        }
+    }
+    (BigUint::from(largest.0), largest.1)
+}
+
+/// Finds the `n`th prime number, counting from `nth_prime(1) == 2`.
+///
+/// The sieve limit starts at the upper bound `n (ln n + ln ln n)` from `nth_prime_upper_bound`, and is
+/// doubled until the sieve holds at least `n` primes, so the result does not depend on the bound being
+/// tight or even correct.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing the 1-based index of the prime. It must be at least 1.
+///
+/// # Returns
+///
+/// The `n`th prime as a `BigUint`.
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::nth_prime;
+///
+/// assert_eq!(nth_prime(6), BigUint::from(13u32));
+/// ```
+pub fn nth_prime(n: u64) -> BigUint {
+    assert!(n >= 1, "nth_prime is 1-indexed, n must be at least 1");
+
+    let mut limit = nth_prime_upper_bound(n);
+    loop {
+        let primes = get_max_primes_u64(limit);
+        if let Some(&p) = primes.get((n - 1) as usize) {
+            return BigUint::from(p);
+        }
+        limit = limit.saturating_mul(2);
+    }
+}
+
+/// Finds the first primes in an arithmetic progression.
+///
+/// Each term `a + k*d` is tested with the Baillie-PSW test in increasing order of `k`. When `a` and `d`
+/// share a factor `g > 1` every term is a multiple of `g`, so only terms up to `g` can be prime and the
+/// search stops there instead of running forever. When `d` is 0 the progression holds the single number `a`.
+///
+/// # Arguments
+///
+/// * `a` - A `u64` representing the first term of the progression.
+/// * `d` - A `u64` representing the common difference of the progression.
+/// * `count` - A `usize` representing how many primes to find.
+///
+/// # Returns
+///
+/// A vector of `BigUint` containing at most `count` primes of the form `a + k*d`, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::sieve_arithmetic_progression;
+///
+/// let primes = sieve_arithmetic_progression(3, 4, 3);
+/// assert_eq!(primes, vec![BigUint::from(3u32), BigUint::from(7u32), BigUint::from(11u32)]);
+/// ```
+pub fn sieve_arithmetic_progression(a: u64, d: u64, count: usize) -> Vec<BigUint> {
+    // Every term of a constant progression is `a`, which must be reported at most once
+    if d == 0 {
+        let term = BigUint::from(a);
+        return if count > 0 && baillie_psw(&term) { vec![term] } else { Vec::new() };
+    }
+
+    let mut primes = Vec::with_capacity(count);
+    let step = BigUint::from(d);
+    let mut term = BigUint::from(a);
+
+    // Without a common factor Dirichlet's theorem guarantees infinitely many primes
+    let limit = if is_coprime(&term, &step) {
+        None
+    } else {
+        Some(gcd(&term, &step))
+    };
+
+    while primes.len() < count {
+        if let Some(limit) = &limit {
+            if term > *limit {
+                break;
+            }
+        }
+        if baillie_psw(&term) {
+            primes.push(term.clone());
+        }
+        term += &step;
+    }
+    primes
+}
+
+/// Checks whether a number is a right-truncatable prime.
+///
+/// A right-truncatable prime stays prime as its last decimal digit is removed repeatedly, for example
+/// 3797, 379, 37 and 3. Every truncation is tested with `miller_rabin`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to check.
+///
+/// # Returns
+///
+/// * `true` if `n` and all of its right truncations are prime.
+/// * `false` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_right_truncatable_prime;
+///
+/// assert_eq!(is_right_truncatable_prime(&BigUint::from(3797u32)), true);
+/// assert_eq!(is_right_truncatable_prime(&BigUint::from(3799u32)), false);
+/// ```
+pub fn is_right_truncatable_prime(n: &BigUint) -> bool {
+    let ten = BigUint::from(10u32);
+    let mut current = n.clone();
+    while !current.is_zero() {
+        if !miller_rabin(&current) {
+            return false;
+        }
+        current /= &ten;
+    }
+    !n.is_zero()
+}
+
+/// Checks whether a number is a left-truncatable prime.
+///
+/// A left-truncatable prime stays prime as its first decimal digit is removed repeatedly, for example
+/// 3137, 137, 37 and 7. Numbers containing a zero digit are rejected, since dropping digits in front of
+/// a zero would leave a leading zero. Every truncation is tested with `miller_rabin`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to check.
+///
+/// # Returns
+///
+/// * `true` if `n` has no zero digits and it and all of its left truncations are prime.
+/// * `false` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_left_truncatable_prime;
+///
+/// assert_eq!(is_left_truncatable_prime(&BigUint::from(3137u32)), true);
+/// assert_eq!(is_left_truncatable_prime(&BigUint::from(103u32)), false);
+/// ```
+pub fn is_left_truncatable_prime(n: &BigUint) -> bool {
+    let digits = n.to_str_radix(10);
+    if digits.contains('0') {
+        return false;
+    }
+    (0..digits.len()).all(|start| {
+        let suffix: BigUint = digits[start..].parse().unwrap();
+        miller_rabin(&suffix)
+    })
+}
+
+/// Checks whether a number is a circular prime.
+///
+/// A circular prime stays prime under every rotation of its decimal digits, for example 197, 971 and
+/// 719. Every rotation is tested with `miller_rabin`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to check.
+///
+/// # Returns
+///
+/// * `true` if every digit rotation of `n` is prime.
+/// * `false` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_circular_prime;
+///
+/// assert_eq!(is_circular_prime(&BigUint::from(197u32)), true);
+/// assert_eq!(is_circular_prime(&BigUint::from(23u32)), false);
+/// ```
+pub fn is_circular_prime(n: &BigUint) -> bool {
+    let digits = n.to_str_radix(10);
+    (0..digits.len()).all(|shift| {
+        let rotation = format!("{}{}", &digits[shift..], &digits[..shift]);
+        let rotation: BigUint = rotation.parse().unwrap();
+        miller_rabin(&rotation)
+    })
+}
+
+/// Generates all Carmichael numbers up to a given maximum value.
+///
+/// Carmichael numbers are composites that pass the Fermat test for every coprime base. They are found with
+/// Korselt's criterion: a composite `n` is a Carmichael number if and only if it is squarefree and `p - 1`
+/// divides `n - 1` for every prime factor `p` of `n`.
+///
+/// # Arguments
+///
+/// * `max` - A `u64` representing the maximum value up to which Carmichael numbers are generated.
+///
+/// # Returns
+///
+/// A vector of `BigUint` containing all Carmichael numbers less than or equal to `max`, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::carmichael_numbers_up_to;
+///
+/// assert_eq!(carmichael_numbers_up_to(1000), vec![BigUint::from(561u32)]);
+/// ```
+pub fn carmichael_numbers_up_to(max: u64) -> Vec<BigUint> {
+    let mut carmichaels: Vec<BigUint> = Vec::new();
+
+    // Carmichael numbers are odd, so even candidates are skipped
+    for n in (3..=max).step_by(2) {
+        let n = BigUint::from(n);
+        let factors = factorize(&n);
+        if factors.len() < 2 {
+            continue;
+        }
+
+        let n_minus_one = &n - BigUint::one();
+        let korselt = factors
+            .iter()
+            .all(|(p, exponent)| *exponent == 1 && &n_minus_one % (p - BigUint::one()) == BigUint::zero());
+        if korselt {
+            carmichaels.push(n);
+        }
+    }
+    carmichaels
+}
+
+/// Generates all prime numbers up to a given maximum value as an asynchronous stream.
+///
+/// The range is sieved one segment at a time, and each segment is only sieved once the consumer has
+/// drained the previous one. This lets async pipelines apply backpressure, and keeps memory proportional
+/// to the segment size instead of `maximum`.
+///
+/// This function is only available with the `async` feature enabled.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which prime numbers are to be generated.
+///
+/// # Returns
+///
+/// A `Stream` yielding all prime numbers less than or equal to `maximum`, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use num_bigint::BigUint;
+/// use large_primes::prime_stream;
+///
+/// let primes: Vec<BigUint> = futures::executor::block_on(prime_stream(10).collect());
+/// assert_eq!(primes, vec![BigUint::from(2u32), BigUint::from(3u32), BigUint::from(5u32), BigUint::from(7u32)]);
+/// ```
+#[cfg(feature = "async")]
+pub fn prime_stream(maximum: u64) -> impl futures::Stream<Item = BigUint> {
+    use futures::stream::{ self, StreamExt };
+
+    // u64::MAX = 2^64 - 1 is composite, so leaving it out of the range is harmless
+    let end = maximum.saturating_add(1);
+    let base_primes = get_max_primes_u64((maximum as f64).sqrt() as u64 + 1);
+    let segments = (0..=maximum / SEGMENT_SIZE).map(move |i| {
+        let low = i * SEGMENT_SIZE;
+        let high = low.saturating_add(SEGMENT_SIZE).min(end);
+        (low, high)
+    });
+
+    stream::iter(segments).flat_map(move |(low, high)| {
+        let primes = sieve_segment(low, high, &base_primes);
+        stream::iter(primes.into_iter().map(BigUint::from))
+    })
+}
+
+/// Generates the prime numbers in the range `[low, high)` with a segmented sieve of Eratosthenes.
+///
+/// The base primes up to `sqrt(high)` are sieved first, and the range is then sieved in blocks of 65536
+/// numbers. Memory use is proportional to the block size plus `sqrt(high)` rather than to the whole range,
+/// so ranges far above what `get_max_primes` can allocate work fine.
+///
+/// # Arguments
+///
+/// * `low` - A `u64` representing the inclusive lower end of the range.
+/// * `high` - A `u64` representing the exclusive upper end of the range.
+///
+/// # Returns
+///
+/// A vector of `BigUint` containing all primes `p` with `low <= p < high`, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::get_primes_in_range;
+///
+/// let primes = get_primes_in_range(10_000_000_000, 10_000_000_100);
+/// assert_eq!(primes.first(), Some(&BigUint::from(10_000_000_019u64)));
+/// ```
+pub fn get_primes_in_range(low: u64, high: u64) -> Vec<BigUint> {
+    if low >= high {
+        return Vec::new();
+    }
+
+    let base_primes = get_max_primes_u64((high as f64).sqrt() as u64 + 1);
+    let mut primes = Vec::new();
+    let mut segment_low = low;
+    while segment_low < high {
+        let segment_high = segment_low.saturating_add(SEGMENT_SIZE).min(high);
+        primes.extend(sieve_segment(segment_low, segment_high, &base_primes).into_iter().map(BigUint::from));
+        segment_low = segment_high;
+    }
+    primes
+}
+
+/// Number of values sieved at once by the segmented sieve.
+const SEGMENT_SIZE: u64 = 1 << 16;
+
+/// Sieves the segment `[low, high)` using base primes covering at least `sqrt(high)`, returning the
+/// primes found in the segment.
+fn sieve_segment(low: u64, high: u64, base_primes: &[u64]) -> Vec<u64> {
+    let mut is_prime = vec![true; (high - low) as usize];
+    for &p in base_primes {
+        if p * p >= high {
+            break;
+        }
+        // Near u64::MAX the next multiple of p may not exist
+        let Some(first) = low.div_ceil(p).checked_mul(p) else {
+            continue;
+        };
+        let mut multiple = (p * p).max(first);
+        while multiple < high {
+            is_prime[(multiple - low) as usize] = false;
+            multiple = match multiple.checked_add(p) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    }
+
+    is_prime
+        .iter()
+        .enumerate()
+        .map(|(i, flag)| (low + i as u64, flag))
+        .filter(|(n, flag)| **flag && *n >= 2)
+        .map(|(n, _)| n)
+        .collect()
+}
+
+/// Computes a stable checksum over a list of primes, to detect corruption of cached prime lists.
+///
+/// The checksum is the 64-bit FNV-1a hash of the decimal representations of the primes, each followed by
+/// a comma so that `[2, 3]` and `[23]` hash differently. It does not depend on the platform or on the
+/// Rust version, so checksums can be stored alongside the list and checked after reading it back.
+/// It is not a cryptographic hash and offers no protection against deliberate tampering.
+///
+/// # Arguments
+///
+/// * `primes` - A slice of `BigUint` values to hash.
+///
+/// # Returns
+///
+/// A `u64` checksum of the list.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::{ get_max_primes, prime_list_checksum };
+///
+/// let primes = get_max_primes(100);
+/// assert_eq!(prime_list_checksum(&primes), prime_list_checksum(&get_max_primes(100)));
+/// assert_ne!(prime_list_checksum(&primes), prime_list_checksum(&primes[1..]));
+/// ```
+pub fn prime_list_checksum(primes: &[BigUint]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for prime in primes {
+        for byte in prime.to_str_radix(10).bytes().chain(std::iter::once(b',')) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+/// Runs the Sieve of Eratosthenes, returning a flag for every number from 0 to `maximum` telling
+/// whether it is prime. Returns an empty vector if `maximum` is less than 2.
+///
+/// Panics with a clear message if `maximum + 1` flags cannot be allocated on this platform, instead of
+/// overflowing or truncating the size.
+fn sieve(maximum: u64) -> Vec<bool> {
     if maximum < 2 {
-        return Vec::<BigUint>::new();
+        return Vec::new();
+    }
+    let size = usize::try_from(maximum)
+        .ok()
+        .and_then(|maximum| maximum.checked_add(1));
+    let mut sieve = Vec::new();
+    match size {
+        Some(size) if sieve.try_reserve_exact(size).is_ok() => sieve.resize(size, true),
+        _ => panic!("cannot sieve up to {}: the sieve does not fit in memory on this platform", maximum),
+    }
+    sieve[0] = false;
+    sieve[1] = false;
+    for i in (2..maximum + 1).take_while(|i| i * i <= maximum) {
+        if sieve[i as usize] {
+            let mut j = i * i;
+            while j < maximum + 1 {
+                sieve[j as usize] = false;
+                j += i;
+            }
+        }
+    }
+    sieve
+}
+
+/// Counts the prime numbers less than or equal to a given maximum value.
+///
+/// This is the prime-counting function `π(maximum)`, computed with the Sieve of Eratosthenes.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which primes are counted.
+///
+/// # Returns
+///
+/// The number of primes less than or equal to `maximum`.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::prime_pi;
+///
+/// assert_eq!(prime_pi(10), 4);
+/// assert_eq!(prime_pi(100), 25);
+/// ```
+pub fn prime_pi(maximum: u64) -> u64 {
+    sieve(maximum)
+        .iter()
+        .filter(|is_prime| **is_prime)
+        .count() as u64
+}
+
+/// Compares the actual density of primes up to a maximum with the prime number theorem's estimate.
+///
+/// The prime number theorem states that the density of primes around `maximum` approaches `1 / ln(maximum)`.
+/// This function returns the actual density `π(maximum) / maximum` alongside that estimate.
+///
+/// # Arguments
+///
+/// * `max` - A `u64` representing the maximum value of the range.
+///
+/// # Returns
+///
+/// A tuple `(actual, estimated)` of densities. Both are `0.0` if `max` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::prime_density;
+///
+/// let (actual, estimated) = prime_density(100);
+/// assert_eq!(actual, 0.25);
+/// assert!((estimated - 0.217).abs() < 0.001);
+/// ```
+pub fn prime_density(max: u64) -> (f64, f64) {
+    if max < 2 {
+        return (0.0, 0.0);
     }
-    let mut primes: Vec<BigUint> = Vec::new();
-    let mut sieve = vec![true; (maximum+1) as usize];
-    sieve[0] = false;
-    sieve[1] = false;
-    for i in 2..maximum + 1 {
-        if sieve[i as usize] {
-            primes.push(BigUint::from(i));
-            let mut j = i * i;
-            while j < maximum + 1 {
-                sieve[j as usize] = false;
-                j += i;
+    let actual = (prime_pi(max) as f64) / (max as f64);
+    let estimated = 1.0 / (max as f64).ln();
+    (actual, estimated)
+}
+
+/// The logarithmic integral at 2, `li(2)`.
+const LI_2: f64 = 1.045_163_780_117_493;
+
+/// Subintervals used by Simpson's rule in `li`.
+const LI_STEPS: u32 = 10_000;
+
+/// Computes the logarithmic integral `li(x)`, the integral of `1 / ln(t)` from 0 to `x`.
+///
+/// `li(x)` estimates `π(x)` much more closely than `x / ln(x)`: at 10^6 it is off by about 130, where
+/// `x / ln(x)` is off by more than 6000. The integral is split as `li(2)` plus the integral from 2 to `x`,
+/// which is computed with Simpson's rule after substituting `t = e^u`, so the integrand `e^u / u` is smooth
+/// over the whole range.
+///
+/// # Arguments
+///
+/// * `x` - An `f64` representing the upper limit of the integral. It must be at least 2.
+///
+/// # Returns
+///
+/// The value of `li(x)`. Returns NaN if `x` is less than 2 or NaN.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::{ li, prime_pi };
+///
+/// let estimate = li(1e6);
+/// assert!((estimate - 78627.549).abs() < 0.01);
+/// assert!((estimate - prime_pi(1000000) as f64).abs() < 200.0);
+/// ```
+pub fn li(x: f64) -> f64 {
+    if x.is_nan() || x < 2.0 {
+        return f64::NAN;
+    }
+
+    let low = 2f64.ln();
+    let high = x.ln();
+    let h = (high - low) / (LI_STEPS as f64);
+    let integrand = |u: f64| u.exp() / u;
+
+    let mut sum = integrand(low) + integrand(high);
+    for i in 1..LI_STEPS {
+        let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+        sum += weight * integrand(low + (i as f64) * h);
+    }
+
+    LI_2 + (sum * h) / 3.0
+}
+
+/// Generates all prime numbers up to a given maximum value in a compact delta-encoded form.
+///
+/// Instead of storing every prime, this function stores the gap between consecutive primes (starting
+/// from 0) as a variable-length integer (LEB128). Since prime gaps are small, almost every gap fits in a
+/// single byte, making the result roughly 4-8x smaller than a `Vec<u64>` of the same primes.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which prime numbers are to be generated.
+///
+/// # Returns
+///
+/// A vector of bytes holding the variable-length encoded gaps. Use `decode_prime_deltas` to reconstruct
+/// the primes. Returns an empty vector if `maximum` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::{ get_max_primes_deltas, decode_prime_deltas };
+///
+/// let encoded = get_max_primes_deltas(10);
+/// assert_eq!(encoded, vec![2, 1, 2, 2]);
+/// assert_eq!(decode_prime_deltas(&encoded), vec![2, 3, 5, 7]);
+/// ```
+pub fn get_max_primes_deltas(maximum: u64) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut previous = 0u64;
+    for prime in get_max_primes(maximum) {
+        let prime = prime.to_u64().unwrap();
+        let mut gap = prime - previous;
+        loop {
+            let byte = (gap & 0x7f) as u8;
+            gap >>= 7;
+            if gap == 0 {
+                bytes.push(byte);
+                break;
             }
+            bytes.push(byte | 0x80);
+        }
+        previous = prime;
+    }
+    bytes
+}
+
+/// Reconstructs a list of primes from the delta encoding produced by `get_max_primes_deltas`.
+///
+/// # Arguments
+///
+/// * `bytes` - A slice of bytes holding variable-length encoded gaps between consecutive primes.
+///
+/// # Returns
+///
+/// A vector of `u64` containing the decoded primes in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::decode_prime_deltas;
+///
+/// assert_eq!(decode_prime_deltas(&[2, 1, 2, 2, 4]), vec![2, 3, 5, 7, 11]);
+/// ```
+pub fn decode_prime_deltas(bytes: &[u8]) -> Vec<u64> {
+    let mut primes: Vec<u64> = Vec::new();
+    let mut current = 0u64;
+    let mut gap = 0u64;
+    let mut shift = 0u32;
+    for byte in bytes {
+        gap |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            current += gap;
+            primes.push(current);
+            gap = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+        }
+    }
+    primes
+}
+
+/// Finds the smallest prime strictly greater than a given number.
+///
+/// Candidates are tested one by one with the Baillie-PSW test, so this is suitable for numbers far beyond
+/// the reach of the sieve.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` after which the next prime is searched for.
+///
+/// # Returns
+///
+/// The smallest prime greater than `num`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::next_prime;
+///
+/// assert_eq!(next_prime(&BigUint::from(90u32)), BigUint::from(97u32));
+/// ```
+pub fn next_prime(num: &BigUint) -> BigUint {
+    let mut candidate = num + BigUint::one();
+    while !baillie_psw(&candidate) {
+        candidate += BigUint::one();
+    }
+    candidate
+}
+
+/// Finds the largest prime strictly smaller than a given number.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` before which the previous prime is searched for.
+///
+/// # Returns
+///
+/// * `Some(prime)` with the largest prime smaller than `num`.
+/// * `None` if `num` is less than or equal to 2, as there is no smaller prime.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::prev_prime;
+///
+/// assert_eq!(prev_prime(&BigUint::from(90u32)), Some(BigUint::from(89u32)));
+/// assert_eq!(prev_prime(&BigUint::from(2u32)), None);
+/// ```
+pub fn prev_prime(num: &BigUint) -> Option<BigUint> {
+    let mut candidate = num.clone();
+    while candidate > BigUint::from(2u32) {
+        candidate -= BigUint::one();
+        if baillie_psw(&candidate) {
+            return Some(candidate);
         }
     }
-    return primes;
+    None
+}
+
+/// Computes the sum of all prime numbers up to a given maximum value.
+///
+/// # Arguments
+///
+/// * `maximum` - A `u64` representing the maximum value up to which primes are summed.
+///
+/// # Returns
+///
+/// A `BigUint` holding the sum of all primes less than or equal to `maximum`.
+/// Returns zero if `maximum` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::sum_of_primes_up_to;
+///
+/// assert_eq!(sum_of_primes_up_to(10), BigUint::from(17u32));
+/// ```
+pub fn sum_of_primes_up_to(maximum: u64) -> BigUint {
+    get_max_primes(maximum).iter().sum()
 }
 
 #[cfg(test)]
 mod tests {
+    use num_traits::ToPrimitive;
+
     #[test]
     fn edge_cases() {
         // Test case 0: Empty
@@ -85,4 +1405,411 @@ mod tests {
         assert_eq!(primes[99999], super::BigUint::from(1299709u32));
         assert_eq!(primes[599999], super::BigUint::from(8960453u32));
     }
+
+    #[test]
+    fn delta_round_trip() {
+        let encoded = super::get_max_primes_deltas(10000);
+        let decoded = super::decode_prime_deltas(&encoded);
+        let primes: Vec<u64> = super::get_max_primes(10000)
+            .iter()
+            .map(|p| p.to_u64().unwrap())
+            .collect();
+
+        assert_eq!(decoded, primes);
+        assert!(encoded.len() < primes.len() * 8);
+    }
+
+    #[test]
+    fn neighbouring_primes() {
+        let ninety = super::BigUint::from(90u32);
+        assert_eq!(super::next_prime(&ninety), super::BigUint::from(97u32));
+        assert_eq!(super::prev_prime(&ninety), Some(super::BigUint::from(89u32)));
+
+        // Primes themselves are excluded
+        let seven = super::BigUint::from(7u32);
+        assert_eq!(super::next_prime(&seven), super::BigUint::from(11u32));
+        assert_eq!(super::prev_prime(&seven), Some(super::BigUint::from(5u32)));
+
+        // Edge cases
+        assert_eq!(super::next_prime(&super::BigUint::from(0u32)), super::BigUint::from(2u32));
+        assert_eq!(super::prev_prime(&super::BigUint::from(3u32)), Some(super::BigUint::from(2u32)));
+        assert_eq!(super::prev_prime(&super::BigUint::from(2u32)), None);
+    }
+
+    #[test]
+    fn density() {
+        assert_eq!(super::prime_pi(1), 0);
+        assert_eq!(super::prime_pi(2), 1);
+        assert_eq!(super::prime_pi(1000000), 78498);
+
+        assert_eq!(super::prime_density(1), (0.0, 0.0));
+
+        let (actual, estimated) = super::prime_density(1000000);
+        assert!((actual - estimated).abs() / actual < 0.1);
+    }
+
+    #[test]
+    fn logarithmic_integral() {
+        // li(10^6) is within 0.2% of π(10^6) = 78498
+        let estimate = super::li(1e6);
+        assert!((estimate - 78498.0).abs() / 78498.0 < 0.002);
+
+        // Reference values of li(x)
+        let known = [(2.0, 1.045163780117493), (10.0, 6.165599504787297), (1e6, 78627.54915946217), (1e9, 50849234.95700128)];
+        for (x, expected) in known {
+            assert!((super::li(x) - expected).abs() / expected < 1e-9);
+        }
+
+        // Closer to π(x) than x / ln(x)
+        let x = 1e6;
+        assert!((super::li(x) - 78498.0).abs() < (x / f64::ln(x) - 78498.0).abs());
+
+        assert!(super::li(1.5).is_nan());
+        assert!(super::li(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn sum_of_primes() {
+        assert_eq!(super::sum_of_primes_up_to(1), super::BigUint::from(0u32));
+        assert_eq!(super::sum_of_primes_up_to(10), super::BigUint::from(17u32));
+        assert_eq!(super::sum_of_primes_up_to(2000000), super::BigUint::from(142913828922u64));
+    }
+
+    #[test]
+    fn linear_sieve() {
+        use crate::operations::totient::euler_totient;
+
+        let (primes, totients) = super::linear_sieve(1000);
+        assert_eq!(primes, super::get_max_primes_u64(1000));
+
+        for i in 1..=100u64 {
+            assert_eq!(super::BigUint::from(totients[i as usize]), euler_totient(&super::BigUint::from(i)));
+        }
+    }
+
+    #[test]
+    fn mertens() {
+        assert_eq!(super::mertens(0), 0);
+        assert_eq!(super::mertens(1), 1);
+        assert_eq!(super::mertens(2), 0);
+        assert_eq!(super::mertens(10), -1);
+        assert_eq!(super::mertens(1000), 2);
+    }
+
+    #[test]
+    fn abc_triples() {
+        let triples = super::abc_triples_up_to(100, 1.0);
+        assert!(triples.contains(&(5, 27, 32)));
+        assert!(triples.contains(&(1, 8, 9)));
+
+        // 5 + 27 = 32 has quality ln(32) / ln(30) > 1.01
+        assert_eq!(super::abc_triples_up_to(32, 1.01), vec![(1, 8, 9), (5, 27, 32)]);
+        assert!(!super::abc_triples_up_to(100, 1.02).contains(&(5, 27, 32)));
+    }
+
+    #[test]
+    fn first_primes() {
+        assert_eq!(super::first_n_primes(0), Vec::<super::BigUint>::new());
+        assert_eq!(
+            super::first_n_primes(5),
+            [2u32, 3, 5, 7, 11].iter().map(|p| super::BigUint::from(*p)).collect::<Vec<_>>()
+        );
+
+        for n in 1..2000 {
+            let primes = super::first_n_primes(n);
+            assert_eq!(primes.len(), n as usize);
+        }
+        assert_eq!(super::first_n_primes(10000)[9999], super::BigUint::from(104729u32));
+    }
+
+    #[test]
+    fn carmichael_numbers() {
+        let expected: Vec<super::BigUint> = [561u32, 1105, 1729, 2465, 2821, 6601, 8911]
+            .iter()
+            .map(|n| super::BigUint::from(*n))
+            .collect();
+        assert_eq!(super::carmichael_numbers_up_to(10000), expected);
+        assert_eq!(super::carmichael_numbers_up_to(560), Vec::<super::BigUint>::new());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_stream() {
+        use futures::StreamExt;
+
+        for maximum in [0, 1, 2, 30, 65536, 200000] {
+            let streamed: Vec<super::BigUint> = futures::executor::block_on(
+                super::prime_stream(maximum).collect()
+            );
+            assert_eq!(streamed, super::get_max_primes(maximum));
+        }
+    }
+
+    #[test]
+    fn checksum() {
+        let mut primes = super::get_max_primes(100);
+
+        // Pinned value, so that changes to the hash are caught
+        assert_eq!(super::prime_list_checksum(&primes), 0xb1c700ff69efa20b);
+        assert_eq!(super::prime_list_checksum(&[]), 0xcbf29ce484222325);
+
+        primes[10] += 2u32;
+        assert_ne!(super::prime_list_checksum(&primes), 0xb1c700ff69efa20b);
+    }
+
+    #[test]
+    fn arithmetic_progression() {
+        let expected: Vec<super::BigUint> = [5u32, 13, 17, 29, 37].iter().map(|&p| super::BigUint::from(p)).collect();
+        assert_eq!(super::sieve_arithmetic_progression(1, 4, 5), expected);
+
+        // A shared factor leaves at most one prime
+        assert_eq!(super::sieve_arithmetic_progression(2, 4, 3), vec![super::BigUint::from(2u32)]);
+        assert_eq!(super::sieve_arithmetic_progression(4, 6, 3), Vec::<super::BigUint>::new());
+
+        // A constant progression yields its term once if it is prime, and nothing otherwise
+        assert_eq!(super::sieve_arithmetic_progression(5, 0, 3), vec![super::BigUint::from(5u32)]);
+        assert_eq!(super::sieve_arithmetic_progression(5, 0, 0), Vec::<super::BigUint>::new());
+        assert_eq!(super::sieve_arithmetic_progression(4, 0, 1), Vec::<super::BigUint>::new());
+    }
+
+    #[test]
+    fn truncatable_primes() {
+        assert_eq!(super::is_right_truncatable_prime(&super::BigUint::from(3797u32)), true);
+        assert_eq!(super::is_left_truncatable_prime(&super::BigUint::from(3797u32)), true);
+
+        assert_eq!(super::is_left_truncatable_prime(&super::BigUint::from(23u32)), true);
+        assert_eq!(super::is_right_truncatable_prime(&super::BigUint::from(29u32)), true);
+        assert_eq!(super::is_left_truncatable_prime(&super::BigUint::from(29u32)), false);
+
+        assert_eq!(super::is_right_truncatable_prime(&super::BigUint::from(100u32)), false);
+        assert_eq!(super::is_left_truncatable_prime(&super::BigUint::from(100u32)), false);
+        assert_eq!(super::is_right_truncatable_prime(&super::BigUint::from(0u32)), false);
+    }
+
+    #[test]
+    fn circular_primes() {
+        assert_eq!(super::is_circular_prime(&super::BigUint::from(197u32)), true);
+        assert_eq!(super::is_circular_prime(&super::BigUint::from(1193u32)), true);
+        assert_eq!(super::is_circular_prime(&super::BigUint::from(13u32)), true);
+        assert_eq!(super::is_circular_prime(&super::BigUint::from(23u32)), false);
+        assert_eq!(super::is_circular_prime(&super::BigUint::from(101u32)), false);
+        assert_eq!(super::is_circular_prime(&super::BigUint::from(1u32)), false);
+    }
+
+    #[test]
+    fn nth() {
+        assert_eq!(super::nth_prime(1), super::BigUint::from(2u32));
+        assert_eq!(super::nth_prime(6), super::BigUint::from(13u32));
+        assert_eq!(super::nth_prime(1000), super::BigUint::from(7919u32));
+        assert_eq!(super::nth_prime(10000), super::BigUint::from(104729u32));
+        assert_eq!(super::nth_prime(100000), super::BigUint::from(1299709u32));
+    }
+
+    #[test]
+    fn with_property() {
+        let ten = super::BigUint::from(10u32);
+        let seven = super::BigUint::from(7u32);
+        let primes = super::primes_with_property(50, |p| p % &ten == seven);
+        let expected: Vec<super::BigUint> = [7u32, 17, 37, 47].iter().map(|&p| super::BigUint::from(p)).collect();
+        assert_eq!(primes, expected);
+
+        assert_eq!(super::primes_with_property(50, |_| false), Vec::<super::BigUint>::new());
+        assert_eq!(super::primes_with_property(50, |_| true), super::get_max_primes(50));
+    }
+
+    #[test]
+    fn twin_primes() {
+        let to_big = |pairs: &[(u32, u32)]| -> Vec<(super::BigUint, super::BigUint)> {
+            pairs.iter().map(|&(p, q)| (super::BigUint::from(p), super::BigUint::from(q))).collect()
+        };
+        assert_eq!(super::twin_primes_up_to(20), to_big(&[(3, 5), (5, 7), (11, 13), (17, 19)]));
+
+        // (17, 19) straddles a maximum of 18
+        assert_eq!(super::twin_primes_up_to(18), to_big(&[(3, 5), (5, 7), (11, 13)]));
+        assert_eq!(super::twin_primes_up_to(19), to_big(&[(3, 5), (5, 7), (11, 13), (17, 19)]));
+
+        for maximum in 0..5 {
+            assert_eq!(super::twin_primes_up_to(maximum), Vec::new());
+        }
+        assert_eq!(super::twin_primes_up_to(5), to_big(&[(3, 5)]));
+
+        // There are 8169 twin prime pairs below 10^6
+        assert_eq!(super::twin_primes_up_to(1000000).len(), 8169);
+    }
+
+    #[test]
+    fn sophie_germain() {
+        let expected: Vec<super::BigUint> = [2u32, 3, 5, 11, 23].iter().map(|&p| super::BigUint::from(p)).collect();
+        assert_eq!(super::sophie_germain_primes_up_to(25), expected);
+        assert_eq!(super::sophie_germain_primes_up_to(1), Vec::<super::BigUint>::new());
+        assert_eq!(super::sophie_germain_primes_up_to(2), vec![super::BigUint::from(2u32)]);
+
+        // There are 190 Sophie Germain primes below 10^4
+        let primes = super::sophie_germain_primes_up_to(10000);
+        assert_eq!(primes.len(), 190);
+        for p in primes {
+            assert_eq!(super::miller_rabin(&p), true);
+            assert_eq!(super::miller_rabin(&(p * 2u32 + 1u32)), true);
+        }
+    }
+
+    #[test]
+    fn run_length() {
+        // Euler's polynomial n^2 + n + 41
+        let euler = |n: u64| super::BigUint::from(n * n + n + 41);
+        assert_eq!(super::prime_run_length(euler), 40);
+
+        // n^2 - 79n + 1601 takes the values of Euler's polynomial twice, for n = 0 to 79
+        let shifted = |n: u64| super::BigUint::from(n * n + 1601 - 79 * n);
+        assert_eq!(super::prime_run_length(shifted), 80);
+
+        // Composite at 0, and prime only at 0
+        assert_eq!(super::prime_run_length(|_| super::BigUint::from(4u32)), 0);
+        assert_eq!(super::prime_run_length(|n| super::BigUint::from(2 + 2 * n)), 1);
+    }
+
+    #[test]
+    fn merge_segments() {
+        let to_big = |values: &[u32]| -> Vec<super::BigUint> { values.iter().map(|&v| super::BigUint::from(v)).collect() };
+        let segments = vec![to_big(&[2, 3]), to_big(&[5, 7]), to_big(&[11, 13])];
+        assert_eq!(super::merge_sorted_prime_segments(segments), to_big(&[2, 3, 5, 7, 11, 13]));
+
+        // Empty segments are skipped
+        let segments = vec![to_big(&[]), to_big(&[2]), to_big(&[]), to_big(&[3])];
+        assert_eq!(super::merge_sorted_prime_segments(segments), to_big(&[2, 3]));
+        assert_eq!(super::merge_sorted_prime_segments(Vec::new()), to_big(&[]));
+    }
+
+    #[test]
+    fn counts_per_block() {
+        assert_eq!(super::prime_counts_per_block(30, 10), vec![4, 4, 2]);
+        assert_eq!(super::prime_counts_per_block(25, 10), vec![4, 4, 1]);
+        assert_eq!(super::prime_counts_per_block(100, 100), vec![25]);
+        assert_eq!(super::prime_counts_per_block(0, 10), Vec::<u64>::new());
+        assert_eq!(super::prime_counts_per_block(10, 0), Vec::<u64>::new());
+
+        let counts = super::prime_counts_per_block(10000, 7);
+        assert_eq!(counts.iter().sum::<u64>(), super::prime_pi(10000));
+    }
+
+    #[test]
+    fn primes_in_range() {
+        let all = super::get_max_primes(30);
+        assert_eq!(super::get_primes_in_range(10, 30), all[4..].to_vec());
+        assert_eq!(super::get_primes_in_range(0, 31), all);
+        assert_eq!(super::get_primes_in_range(30, 10), Vec::<super::BigUint>::new());
+        assert_eq!(super::get_primes_in_range(24, 29), Vec::<super::BigUint>::new());
+
+        // Ranges spanning several segments agree with the plain sieve
+        let all = super::get_max_primes(300000);
+        let expected: Vec<super::BigUint> = all
+            .into_iter()
+            .filter(|p| *p >= super::BigUint::from(12345u32))
+            .collect();
+        assert_eq!(super::get_primes_in_range(12345, 300001), expected);
+    }
+
+    #[test]
+    fn lazy_primes() {
+        let first: Vec<super::BigUint> = super::primes_up_to(1_000_000).take(10).collect();
+        let expected: Vec<super::BigUint> = [2u32, 3, 5, 7, 11, 13, 17, 19, 23, 29]
+            .iter()
+            .map(|&p| super::BigUint::from(p))
+            .collect();
+        assert_eq!(first, expected);
+
+        assert_eq!(super::primes_up_to(1).next(), None);
+        assert_eq!(super::primes_up_to(2).collect::<Vec<_>>(), vec![super::BigUint::from(2u32)]);
+
+        // Crossing segment boundaries
+        let count = super::primes_up_to(200000).count() as u64;
+        assert_eq!(count, super::prime_pi(200000));
+    }
+
+    #[test]
+    fn counts_by_bitlength() {
+        let counts = super::prime_count_by_bitlength(4);
+        // 2-bit: 2, 3
+        assert_eq!(counts[2], 2);
+        // 3-bit: 5, 7
+        assert_eq!(counts[3], 2);
+        // 4-bit: 11, 13
+        assert_eq!(counts[4], 2);
+        assert_eq!(counts, vec![0, 0, 2, 2, 2]);
+
+        assert_eq!(super::prime_count_by_bitlength(0), vec![0]);
+        assert_eq!(super::prime_count_by_bitlength(1), vec![0, 0]);
+
+        // Cross-check against the sieve: the counts add up to π(2^b - 1)
+        let counts = super::prime_count_by_bitlength(22);
+        for bits in 2..=22u64 {
+            let total: u64 = counts[..=bits as usize].iter().sum();
+            assert_eq!(total, super::prime_pi((1 << bits) - 1));
+        }
+    }
+
+    #[test]
+    fn gaps() {
+        let gaps = super::prime_gaps_up_to(100);
+        assert_eq!(gaps.len(), 24);
+        assert!(gaps.contains(&(super::BigUint::from(23u32), 6)));
+        assert_eq!(gaps.last(), Some(&(super::BigUint::from(89u32), 8)));
+        for (p, gap) in &gaps {
+            assert_eq!(super::next_prime(p), p + gap);
+        }
+        assert_eq!(super::prime_gaps_up_to(2), Vec::new());
+
+        assert_eq!(super::max_prime_gap_up_to(100), (super::BigUint::from(89u32), 8));
+
+        // Maximal prime gaps: each record gap and the prime it starts at
+        let records = [
+            (3u64, 2u64, 5u64), (7, 4, 11), (23, 6, 29), (89, 8, 97), (113, 14, 127),
+            (523, 18, 541), (887, 20, 907), (1129, 22, 1151), (1327, 34, 1361), (9551, 36, 9587),
+            (15683, 44, 15727), (19609, 52, 19661), (31397, 72, 31469), (155921, 86, 156007), (360653, 96, 360749),
+            (370261, 112, 370373), (492113, 114, 492227), (1349533, 118, 1349651), (1357201, 132, 1357333),
+        ];
+        for (p, gap, next) in records {
+            assert_eq!(super::max_prime_gap_up_to(next), (super::BigUint::from(p), gap));
+        }
+
+        assert_eq!(super::max_prime_gap_up_to(2), (super::BigUint::from(0u32), 0));
+    }
+
+    #[test]
+    fn gap_occurrences() {
+        let gaps = super::first_gap_occurrences(100);
+        let expected: Vec<(u64, u32)> = vec![(1, 2), (2, 3), (4, 7), (6, 23), (8, 89)];
+        assert_eq!(
+            gaps.into_iter().collect::<Vec<_>>(),
+            expected.into_iter().map(|(g, p)| (g, super::BigUint::from(p))).collect::<Vec<_>>()
+        );
+
+        // The first gap of 14 is 113 -> 127
+        assert_eq!(super::first_gap_occurrences(1000)[&14], super::BigUint::from(113u32));
+        assert!(super::first_gap_occurrences(2).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in memory")]
+    fn sieve_u64_max() {
+        super::get_max_primes_u64(u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in memory")]
+    fn sieve_near_usize_max() {
+        super::prime_pi(usize::MAX as u64 - 1);
+    }
+
+    #[test]
+    fn segment_near_u64_max() {
+        // Most of these base primes have no multiple left between the segment start and u64::MAX
+        let base_primes = super::get_max_primes_u64(1000);
+        let low = u64::MAX - 100;
+        let survivors = super::sieve_segment(low, u64::MAX, &base_primes);
+        let expected: Vec<u64> = (low..u64::MAX)
+            .filter(|n| base_primes.iter().all(|p| n % p != 0))
+            .collect();
+        assert_eq!(survivors, expected);
+    }
 }
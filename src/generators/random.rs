@@ -0,0 +1,256 @@
+use num_bigint::BigUint;
+use rand::{ RngCore, SeedableRng };
+use rand::rngs::StdRng;
+use crate::primality::miller_rabin;
+
+/// Generates a random prime with the given number of bits.
+///
+/// Random odd numbers with the top bit set are drawn until one passes `miller_rabin`. The numbers come from
+/// `rand::thread_rng`, a cryptographically secure generator seeded by the operating system, so the result
+/// is suitable as a secret. By the prime number theorem about `bits * ln(2) / 2` candidates are tested on
+/// average.
+///
+/// # Arguments
+///
+/// * `bits` - A `u64` representing the bit length of the prime. It must be at least 2.
+///
+/// # Returns
+///
+/// A prime `BigUint` with exactly `bits` bits.
+///
+/// # Panics
+///
+/// Panics if `bits` is less than 2, since no prime has fewer bits.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::{ random_prime, miller_rabin };
+///
+/// let p = random_prime(256);
+/// assert_eq!(p.bits(), 256);
+/// assert!(miller_rabin(&p));
+/// ```
+pub fn random_prime(bits: u64) -> BigUint {
+    assert!(bits >= 2, "random_prime requires at least 2 bits");
+    random_prime_with_rng(bits, &mut rand::thread_rng())
+}
+
+/// Generates a random prime with the given number of bits, reproducibly from a seed.
+///
+/// This works like `random_prime`, but draws from a `StdRng` seeded with `seed`, so the same seed always
+/// gives the same prime. It is meant for tests and examples, not for secrets.
+///
+/// # Arguments
+///
+/// * `bits` - A `u64` representing the bit length of the prime. It must be at least 2.
+/// * `seed` - A `u64` seeding the random number generator.
+///
+/// # Returns
+///
+/// A prime `BigUint` with exactly `bits` bits.
+///
+/// # Panics
+///
+/// Panics if `bits` is less than 2, since no prime has fewer bits.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::random_prime_seeded;
+///
+/// assert_eq!(random_prime_seeded(128, 7), random_prime_seeded(128, 7));
+/// assert_eq!(random_prime_seeded(128, 7).bits(), 128);
+/// ```
+pub fn random_prime_seeded(bits: u64, seed: u64) -> BigUint {
+    assert!(bits >= 2, "random_prime requires at least 2 bits");
+    random_prime_with_rng(bits, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Generates a random safe prime with the given number of bits.
+///
+/// A safe prime is a prime `p` for which `(p - 1) / 2` is also prime, as used for Diffie-Hellman groups.
+/// Random primes `q` of `bits - 1` bits are drawn as in `random_prime` until `p = 2q + 1` also passes
+/// `miller_rabin`. Both conditions have to hold at once, so this takes roughly `bits` times as many
+/// attempts as `random_prime`.
+///
+/// # Arguments
+///
+/// * `bits` - A `u64` representing the bit length of the safe prime. It must be at least 3.
+///
+/// # Returns
+///
+/// A safe prime `BigUint` with exactly `bits` bits.
+///
+/// # Panics
+///
+/// Panics if `bits` is less than 3, since the smallest safe prime is 5.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::{ random_safe_prime, miller_rabin };
+///
+/// let p = random_safe_prime(64);
+/// assert_eq!(p.bits(), 64);
+/// assert!(miller_rabin(&p));
+/// assert!(miller_rabin(&((p - BigUint::from(1u32)) >> 1u32)));
+/// ```
+pub fn random_safe_prime(bits: u64) -> BigUint {
+    assert!(bits >= 3, "random_safe_prime requires at least 3 bits");
+
+    let mut rng = rand::thread_rng();
+    loop {
+        let q = random_prime_with_rng(bits - 1, &mut rng);
+        let p: BigUint = (q << 1u32) + 1u32;
+        if miller_rabin(&p) {
+            return p;
+        }
+    }
+}
+
+/// Generates a random composite number with two large prime factors.
+///
+/// Two random primes of about `bits / 2` bits each are drawn, as in `random_prime`, and multiplied. Such numbers have no small
+/// factors for trial division to find, which makes them good stress tests for primality and
+/// factorization code. Given the same generator state, the same number is produced.
+///
+/// # Arguments
+///
+/// * `bits` - A `u64` representing the approximate bit length of the result. It must be at least 4.
+/// * `rng` - The random number generator to draw from.
+///
+/// # Returns
+///
+/// A composite `BigUint` of `bits - 1` or `bits` bits, the product of a `bits / 2` bit prime and a
+/// `bits - bits / 2` bit prime.
+///
+/// # Panics
+///
+/// Panics if `bits` is less than 4, since each factor needs at least 2 bits.
+///
+/// # Examples
+///
+/// ```
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+/// use large_primes::{ random_composite, miller_rabin };
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let n = random_composite(128, &mut rng);
+/// assert!(n.bits() >= 127);
+/// assert!(!miller_rabin(&n));
+/// ```
+pub fn random_composite(bits: u64, rng: &mut impl RngCore) -> BigUint {
+    assert!(bits >= 4, "random_composite requires at least 4 bits");
+
+    let p = random_prime_with_rng(bits / 2, rng);
+    let q = random_prime_with_rng(bits - bits / 2, rng);
+    p * q
+}
+
+/// Draws random odd `bits`-bit numbers, with the top bit set, until one passes `miller_rabin`.
+///
+/// `bits` is expected to be at least 2.
+pub(crate) fn random_prime_with_rng(bits: u64, rng: &mut impl RngCore) -> BigUint {
+    let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+    loop {
+        rng.fill_bytes(&mut bytes);
+        let mut candidate = BigUint::from_bytes_le(&bytes) >> (bytes.len() as u64 * 8 - bits);
+        candidate.set_bit(bits - 1, true);
+        candidate.set_bit(0, true);
+        if miller_rabin(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primality::standard;
+    use crate::factorization::factorize;
+
+    #[test]
+    fn random_primes() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for bits in 2..=40 {
+            let p = random_prime_with_rng(bits, &mut rng);
+            assert_eq!(p.bits(), bits);
+            assert_eq!(standard(&p), true);
+        }
+    }
+
+    #[test]
+    fn composites() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for bits in [4u64, 5, 16, 33, 40] {
+            let n = random_composite(bits, &mut rng);
+            assert!(n.bits() == bits || n.bits() == bits - 1);
+            assert_eq!(standard(&n), false);
+            assert_eq!(miller_rabin(&n), false);
+
+            // Exactly two prime factors, of about half the bits each
+            let factors = factorize(&n);
+            let count: u32 = factors.iter().map(|(_, exponent)| exponent).sum();
+            assert_eq!(count, 2);
+            for (p, _) in factors {
+                assert!(p.bits() == bits / 2 || p.bits() == bits - bits / 2);
+            }
+        }
+
+        for bits in [256u64, 512] {
+            let n = random_composite(bits, &mut rng);
+            assert!(n.bits() == bits || n.bits() == bits - 1);
+            assert_eq!(miller_rabin(&n), false);
+        }
+    }
+
+    #[test]
+    fn seeded_replay() {
+        let first = random_composite(128, &mut StdRng::seed_from_u64(42));
+        let second = random_composite(128, &mut StdRng::seed_from_u64(42));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 4 bits")]
+    fn too_few_bits() {
+        random_composite(3, &mut StdRng::seed_from_u64(0));
+    }
+
+    #[test]
+    fn seeded_primes() {
+        for bits in 2..=32 {
+            for seed in 0..4 {
+                let p = random_prime_seeded(bits, seed);
+                assert_eq!(p.bits(), bits);
+                assert_eq!(standard(&p), true);
+                assert_eq!(random_prime_seeded(bits, seed), p);
+            }
+        }
+
+        for bits in [64u64, 512] {
+            let p = random_prime(bits);
+            assert_eq!(p.bits(), bits);
+            assert_eq!(miller_rabin(&p), true);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2 bits")]
+    fn prime_too_few_bits() {
+        random_prime(1);
+    }
+
+    #[test]
+    fn safe_primes() {
+        for bits in [3u64, 4, 5, 16, 64, 128] {
+            let p = random_safe_prime(bits);
+            assert_eq!(p.bits(), bits);
+            assert_eq!(miller_rabin(&p), true);
+            assert_eq!(miller_rabin(&((&p - 1u32) >> 1u32)), true);
+        }
+    }
+}
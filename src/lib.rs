@@ -1,7 +1,147 @@
+//! # Without `std`
+//!
+//! The `std` feature is enabled by default. With `default-features = false` the library is `#![no_std]`
+//! and only needs `alloc`. The following items are available in that configuration:
+//!
+//! * Primality tests: `standard`, `fermat`, `is_fermat_pseudoprime`, `miller_rabin`, `miller_rabin_witness`,
+//!   `miller_rabin_probabilistic`, `miller_rabin_rounds_seeded`, `least_mr_witness`, `baillie_psw`,
+//!   `lucas_probable_prime`, `aks`, `solovay_strassen_or_exact`, `lucas_lehmer_test`, `mersenne_trial_factor`,
+//!   `is_wagstaff_prime`, `PrimalityResult`, and the `generic` module with the `PrimeInt` trait.
+//! * Operations: `gcd`, `lcm`, `gcd_steps`, `extended_gcd`, `is_coprime`, `coprime_pairs_up_to`, `pow`,
+//!   `pow_mod`, `pow_mod_many`, `pow_mod_trace`, `is_perfect_power`, `euler_totient`, `jacobi`, `mod_inverse`,
+//!   `strip_small_factors`, `sort_and_dedup_factors`, and the `utils` module.
+//!
+//! Everything else needs `std`: the prime generators, factorization, parsing, the automatic test selection
+//! in `is_prime`, and the functions that read the clock or draw from the operating system's random number
+//! generator. The command line tool always builds with `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
+extern crate alloc;
+
 mod primality;
 mod operations;
+#[cfg(feature = "std")]
 mod generators;
+#[cfg(feature = "std")]
+mod parse;
+#[cfg(feature = "std")]
+mod factorization;
 
-pub use generators::get_max_primes;
-pub use primality::{ standard, fermat, miller_rabin, lucas_lehmer_test };
-pub use operations::{ gcd, pow_mod, pow, utils };
+#[cfg(feature = "std")]
+pub use generators::{
+    get_max_primes,
+    get_max_primes_deltas,
+    decode_prime_deltas,
+    next_prime,
+    prev_prime,
+    prime_pi,
+    prime_density,
+    sum_of_primes_up_to,
+    get_max_primes_u64,
+    linear_sieve,
+    mertens,
+    abc_triples_up_to,
+    nth_prime_upper_bound,
+    first_n_primes,
+    carmichael_numbers_up_to,
+    prime_list_checksum,
+    sieve_arithmetic_progression,
+    is_right_truncatable_prime,
+    is_left_truncatable_prime,
+    is_circular_prime,
+    nth_prime,
+    primes_with_property,
+    merge_sorted_prime_segments,
+    prime_counts_per_block,
+    get_primes_in_range,
+    primes_up_to,
+    PrimeIter,
+    first_gap_occurrences,
+    prime_run_length,
+    random_composite,
+    li,
+    random_prime,
+    random_prime_seeded,
+    prime_count_by_bitlength,
+    sophie_germain_primes_up_to,
+    random_safe_prime,
+    twin_primes_up_to,
+    prime_gaps_up_to,
+    max_prime_gap_up_to,
+};
+pub use primality::{
+    standard,
+    fermat,
+    is_fermat_pseudoprime,
+    miller_rabin,
+    miller_rabin_probabilistic,
+    miller_rabin_rounds_seeded,
+    miller_rabin_witness,
+    least_mr_witness,
+    baillie_psw,
+    lucas_probable_prime,
+    aks,
+    solovay_strassen_or_exact,
+    lucas_lehmer_test,
+    mersenne_trial_factor,
+    is_wagstaff_prime,
+    PrimeInt,
+    PrimalityResult,
+    generic,
+};
+#[cfg(feature = "std")]
+pub use primality::{
+    standard_within_budget,
+    miller_rabin_random_seeded,
+    miller_rabin_rounds,
+    pseudoprime_density,
+    solovay_strassen,
+    is_prime,
+    is_prime_with_policy,
+    is_prime_recommended,
+    PrimalityPolicy,
+    CompositeBloom,
+    is_likely_prime,
+    race_primality,
+    export_test_corpus,
+    Accuracy,
+};
+#[cfg(feature = "async")]
+pub use generators::prime_stream;
+#[cfg(feature = "parallel")]
+pub use primality::{ standard_parallel, miller_rabin_batch };
+#[cfg(feature = "std")]
+pub use parse::{ parse_flexible, ParseError };
+#[cfg(feature = "std")]
+pub use factorization::{ pollard_rho, pollard_p_minus_1, factorize, factorize_with_progress };
+pub use operations::{
+    gcd,
+    lcm,
+    gcd_steps,
+    extended_gcd,
+    is_coprime,
+    coprime_pairs_up_to,
+    pow_mod,
+    pow_mod_many,
+    pow_mod_trace,
+    pow,
+    is_perfect_power,
+    euler_totient,
+    jacobi,
+    mod_inverse,
+    strip_small_factors,
+    sort_and_dedup_factors,
+    utils,
+};
+#[cfg(feature = "std")]
+pub use operations::{
+    totient_sum,
+    radical,
+    is_squarefree,
+    num_divisors,
+    sum_divisors,
+    jacobsthal,
+    prime_signature,
+};
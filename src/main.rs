@@ -1,49 +1,322 @@
 mod parser;
 
 use clap::Parser;
-use std::time::Instant;
-use parser::Args;
-use large_primes::get_max_primes;
-use large_primes::{ standard, fermat, miller_rabin, lucas_lehmer_test };
-use large_primes::pow;
+use std::io::{ BufRead, Write };
+use std::time::{ Duration, Instant };
+use parser::{ ArgError, Args, TimeUnit };
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use large_primes::{ get_max_primes, next_prime, prev_prime, nth_prime, sum_of_primes_up_to, random_prime };
+use large_primes::{ num_divisors, sum_divisors, factorize, strip_small_factors };
+use large_primes::{ standard, fermat, miller_rabin, baillie_psw, solovay_strassen, lucas_lehmer_test };
+use large_primes::{ pow, parse_flexible };
+use large_primes::utils::is_repunit_prime;
+
+/// Random bases tried by the `solovay-strassen` action, for an error bound of 2^-20.
+const SOLOVAY_STRASSEN_ROUNDS: usize = 20;
 
 fn main() {
     let args = Args::parse();
 
     let now = Instant::now();
 
+    if let Err(error) = run(&args) {
+        println!("{}", error);
+        std::process::exit(1);
+    }
+    print_elapsed(now.elapsed(), args.get_time_unit());
+}
+
+fn run(args: &Args) -> Result<(), ArgError> {
+    if args.get_stdin() {
+        let action = args.get_action();
+        if !is_primality_action(&action) {
+            return Err(ArgError::Invalid("--stdin only works with the primality test actions".to_string()));
+        }
+        let stdin = std::io::stdin();
+        let stdout = std::io::stdout();
+        batch_reports(&action, stdin.lock(), &mut stdout.lock());
+        return Ok(());
+    }
+
     match args.get_action() {
+        action @ (parser::Action::Standard
+        | parser::Action::Fermat
+        | parser::Action::MillerRabin
+        | parser::Action::BailliePsw
+        | parser::Action::SolovayStrassen) => {
+            let target = args.get_target()?;
+            println!("{}", primality_report(&action, &target).unwrap());
+        }
         parser::Action::Power => {
-            let target = args.get_target();
-            let power = args.get_power();
+            let target = args.get_target()?;
+            let power = args.get_power()?;
             println!("Prime power {}: {}", target, pow(&target, &power));
         }
-        parser::Action::Standard => {
-            let target = args.get_target();
-            let is_prime = standard(&target);
-            println!("Standard Test: {} is prime: {}", target, is_prime);
-        }
-        parser::Action::Fermat => {
-            let target = args.get_target();
-            let is_prime = fermat(&target);
-            println!("Fermat Test: {} is prime: {}", target, is_prime);
-        }
         parser::Action::Generate => {
-            let maximum = args.get_maximum();
+            let maximum = args.get_maximum()?;
             let primes = get_max_primes(maximum);
             println!("Primes upto {}: {:?}", maximum, primes);
         }
-        parser::Action::MillerRabin => {
-            let target = args.get_target();
-            let is_prime = miller_rabin(&target);
-            println!("Miller Rabin Test: {} is prime: {}", target, is_prime);
+        parser::Action::Repunit => {
+            let n = args
+                .get_target()?
+                .to_u64()
+                .ok_or_else(|| ArgError::Invalid(format!("The repunit length must be at most {}", u64::MAX)))?;
+            println!("{}", repunit_report(n, args.get_base()));
         }
         parser::Action::LucasLehmer => {
-            let exp = args.get_mercenne_exp();
+            let exp = args.get_mercenne_exp()?;
             let is_prime = lucas_lehmer_test(&exp);
             println!("Lucas Lehmer Test: M{} is prime: {}", exp, is_prime);
         }
+        parser::Action::Gap => {
+            let target = args.get_target()?;
+            println!("{}", gap_report(&target));
+        }
+        parser::Action::SumPrimes => {
+            let maximum = args.get_maximum()?;
+            println!("{}", sum_primes_report(maximum));
+        }
+        parser::Action::Divisors => {
+            let target = args.get_target()?;
+            println!("{}", divisors_report(&target));
+        }
+        parser::Action::Factorize => {
+            let target = args.get_target()?;
+            println!("{}", factorize_report(&target));
+        }
+        parser::Action::StripFactors => {
+            let target = args.get_target()?;
+            println!("{}", strip_factors_report(&target, args.get_limit()));
+        }
+        parser::Action::RandomPrime => {
+            let bits = args.get_bits()?;
+            if bits < 2 {
+                return Err(ArgError::Invalid("A prime needs at least 2 bits".to_string()));
+            }
+            println!("Random {} bit prime: {}", bits, random_prime(bits));
+        }
+        parser::Action::NthPrime => {
+            let n = args
+                .get_target()?
+                .to_u64()
+                .filter(|&n| n >= 1)
+                .ok_or_else(|| ArgError::Invalid(format!("The prime index must be between 1 and {}", u64::MAX)))?;
+            println!("{}", nth_prime_report(n));
+        }
+    }
+    Ok(())
+}
+
+fn print_elapsed(taken: Duration, unit: Option<TimeUnit>) {
+    match unit {
+        Some(unit) => eprintln!("{}", format_duration(taken, &unit)),
+        None => eprint!("Total time: {:?}", taken),
+    }
+}
+
+fn format_duration(duration: Duration, unit: &TimeUnit) -> String {
+    let nanos = duration.as_nanos();
+    match unit {
+        TimeUnit::Ns => nanos.to_string(),
+        TimeUnit::Us => ((nanos as f64) / 1e3).to_string(),
+        TimeUnit::Ms => ((nanos as f64) / 1e6).to_string(),
+        TimeUnit::S => ((nanos as f64) / 1e9).to_string(),
+    }
+}
+
+/// Checks whether an action is one of the primality tests handled by `primality_report`.
+fn is_primality_action(action: &parser::Action) -> bool {
+    matches!(
+        action,
+        parser::Action::Standard
+            | parser::Action::Fermat
+            | parser::Action::MillerRabin
+            | parser::Action::BailliePsw
+            | parser::Action::SolovayStrassen
+    )
+}
+
+/// Runs a primality test action on `target`, or returns `None` for actions that are not primality tests.
+fn primality_report(action: &parser::Action, target: &BigUint) -> Option<String> {
+    let report = match action {
+        parser::Action::Standard => format!("Standard Test: {} is prime: {}", target, standard(target)),
+        parser::Action::Fermat => format!("Fermat Test: {} is prime: {}", target, fermat(target)),
+        parser::Action::MillerRabin => format!("Miller Rabin Test: {} is prime: {}", target, miller_rabin(target)),
+        parser::Action::BailliePsw => format!("Baillie PSW Test: {} is prime: {}", target, baillie_psw(target)),
+        parser::Action::SolovayStrassen => {
+            let is_prime = solovay_strassen(target, SOLOVAY_STRASSEN_ROUNDS);
+            format!("Solovay Strassen Test: {} is prime: {}", target, is_prime)
+        }
+        _ => {
+            return None;
+        }
+    };
+    Some(report)
+}
+
+/// Runs a primality test action on every line of `input`, writing one result per line to `output`.
+///
+/// Blank lines are skipped, and lines that are not numbers get an error line instead of stopping the batch.
+fn batch_reports(action: &parser::Action, input: impl BufRead, output: &mut impl Write) {
+    for line in input.lines() {
+        let report = match line {
+            Ok(line) if line.trim().is_empty() => {
+                continue;
+            }
+            Ok(line) => match parse_flexible(line.trim()) {
+                Ok(target) => primality_report(action, &target).unwrap_or_default(),
+                Err(error) => format!("Error: {}", error),
+            },
+            Err(error) => format!("Could not read line: {}", error),
+        };
+        if writeln!(output, "{}", report).is_err() {
+            return;
+        }
+    }
+}
+
+fn gap_report(target: &BigUint) -> String {
+    let next = next_prime(target);
+    match prev_prime(target) {
+        Some(prev) => {
+            let gap = &next - &prev;
+            format!("Prime gap around {}: previous prime {}, next prime {}, gap {}", target, prev, next, gap)
+        }
+        None => format!("Prime gap around {}: no previous prime, next prime {}", target, next),
+    }
+}
+
+fn sum_primes_report(maximum: u64) -> String {
+    format!("Sum of primes up to {}: {}", maximum, sum_of_primes_up_to(maximum))
+}
+
+fn divisors_report(target: &BigUint) -> String {
+    format!("d({}) = {}, sigma({}) = {}", target, num_divisors(target), target, sum_divisors(target))
+}
+
+fn factorize_report(target: &BigUint) -> String {
+    let factors = factorize(target);
+    if factors.is_empty() {
+        return format!("{} has no prime factors", target);
+    }
+    format!("{} = {}", target, format_factors(&factors))
+}
+
+fn strip_factors_report(target: &BigUint, limit: u64) -> String {
+    let (factors, cofactor) = strip_small_factors(target, limit);
+    let small = if factors.is_empty() { "none".to_string() } else { format_factors(&factors) };
+    let kind = if cofactor <= BigUint::from(1u32) {
+        "unit"
+    } else if miller_rabin(&cofactor) {
+        "prime"
+    } else {
+        "composite"
+    };
+    format!("{}: factors below {}: {}, cofactor {} ({})", target, limit, small, cofactor, kind)
+}
+
+/// Writes `(prime, exponent)` pairs as `p1^e1 * p2 * ...`, leaving out exponents of 1.
+fn format_factors(factors: &[(BigUint, u32)]) -> String {
+    let terms: Vec<String> = factors
+        .iter()
+        .map(|(prime, exponent)| if *exponent == 1 { prime.to_string() } else { format!("{}^{}", prime, exponent) })
+        .collect();
+    terms.join(" * ")
+}
+
+fn nth_prime_report(n: u64) -> String {
+    format!("The {}th prime is {}", n, nth_prime(n))
+}
+
+fn repunit_report(n: u64, base: u64) -> String {
+    let verdict = if is_repunit_prime(n, base) { "prime" } else { "composite" };
+    format!("Repunit R_{} in base {} is {}", n, base, verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gap_action() {
+        assert_eq!(
+            gap_report(&BigUint::from(90u32)),
+            "Prime gap around 90: previous prime 89, next prime 97, gap 8"
+        );
+        assert_eq!(
+            gap_report(&BigUint::from(2u32)),
+            "Prime gap around 2: no previous prime, next prime 3"
+        );
+    }
+
+    #[test]
+    fn sum_primes_action() {
+        assert_eq!(sum_primes_report(10), "Sum of primes up to 10: 17");
+    }
+
+    #[test]
+    fn time_formatting() {
+        let duration = Duration::from_micros(1500);
+        assert_eq!(format_duration(duration, &TimeUnit::Ns), "1500000");
+        assert_eq!(format_duration(duration, &TimeUnit::Us), "1500");
+        assert_eq!(format_duration(duration, &TimeUnit::Ms), "1.5");
+        assert_eq!(format_duration(duration, &TimeUnit::S), "0.0015");
+    }
+
+    #[test]
+    fn divisors_action() {
+        assert_eq!(divisors_report(&BigUint::from(12u32)), "d(12) = 6, sigma(12) = 28");
+    }
+
+    #[test]
+    fn nth_prime_action() {
+        assert_eq!(nth_prime_report(1000), "The 1000th prime is 7919");
+    }
+
+    #[test]
+    fn repunit_action() {
+        assert_eq!(repunit_report(2, 10), "Repunit R_2 in base 10 is prime");
+        assert_eq!(repunit_report(4, 10), "Repunit R_4 in base 10 is composite");
+        assert_eq!(repunit_report(7, 2), "Repunit R_7 in base 2 is prime");
+    }
+
+    #[test]
+    fn factorize_action() {
+        assert_eq!(factorize_report(&BigUint::from(360u32)), "360 = 2^3 * 3^2 * 5");
+        assert_eq!(factorize_report(&BigUint::from(97u32)), "97 = 97");
+        assert_eq!(factorize_report(&BigUint::from(1u32)), "1 has no prime factors");
+    }
+
+    #[test]
+    fn batch_action() {
+        let input = "97\n\n  1,000,000,007  \n12x\n2047\n";
+        let mut output = Vec::new();
+        batch_reports(&parser::Action::MillerRabin, input.as_bytes(), &mut output);
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "Miller Rabin Test: 97 is prime: true");
+        assert_eq!(lines[1], "Miller Rabin Test: 1000000007 is prime: true");
+        assert_eq!(lines[2], "Error: invalid number: 12x");
+        assert_eq!(lines[3], "Miller Rabin Test: 2047 is prime: false");
+
+        assert!(is_primality_action(&parser::Action::MillerRabin));
+        assert!(!is_primality_action(&parser::Action::Generate));
+    }
+
+    #[test]
+    fn strip_factors_action() {
+        assert_eq!(
+            strip_factors_report(&BigUint::from(360u32), 10),
+            "360: factors below 10: 2^3 * 3^2, cofactor 5 (prime)"
+        );
+        assert_eq!(
+            strip_factors_report(&BigUint::from(143u32 * 8), 10),
+            "1144: factors below 10: 2^3, cofactor 143 (composite)"
+        );
+        assert_eq!(strip_factors_report(&BigUint::from(97u32), 5), "97: factors below 5: none, cofactor 97 (prime)");
+        assert_eq!(strip_factors_report(&BigUint::from(64u32), 10), "64: factors below 10: 2^6, cofactor 1 (unit)");
     }
-    let taken = now.elapsed();
-    eprint!("Total time: {:?}", taken);
 }
@@ -0,0 +1,446 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+use num_traits::Zero;
+#[cfg(feature = "std")]
+use num_traits::One;
+#[cfg(feature = "std")]
+use crate::factorization::factorize;
+#[cfg(feature = "std")]
+use super::pow::pow;
+
+/// Removes the prime factors below a limit from a number by trial division.
+///
+/// Divisors from 2 up to `limit - 1` are tried in turn, stopping early once the square of the divisor
+/// exceeds what is left. The cofactor is therefore either 1, a prime, or a number whose prime factors are
+/// all at least `limit`. This is the first stage of `factorize`, which continues on the cofactor with
+/// Pollard's rho.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to factor.
+/// * `limit` - A `u64` bounding the trial divisors from above.
+///
+/// # Returns
+///
+/// A tuple of the `(prime, exponent)` pairs found, sorted ascending by prime, and the remaining cofactor.
+/// The product of the factors and the cofactor equals `n`. For `n = 0` no factors are found and the
+/// cofactor is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::strip_small_factors;
+///
+/// let (factors, cofactor) = strip_small_factors(&BigUint::from(8u32 * 3 * 1000003), 10);
+/// assert_eq!(factors, vec![(BigUint::from(2u32), 3), (BigUint::from(3u32), 1)]);
+/// assert_eq!(cofactor, BigUint::from(1000003u32));
+/// ```
+pub fn strip_small_factors(n: &BigUint, limit: u64) -> (Vec<(BigUint, u32)>, BigUint) {
+    strip_small_factors_with_progress(n, limit, &mut || {})
+}
+
+/// Runs `strip_small_factors`, calling `tick` after every trial divisor.
+pub(crate) fn strip_small_factors_with_progress(
+    n: &BigUint,
+    limit: u64,
+    tick: &mut dyn FnMut(),
+) -> (Vec<(BigUint, u32)>, BigUint) {
+    let mut factors: Vec<(BigUint, u32)> = Vec::new();
+    let mut remaining = n.clone();
+    if remaining.is_zero() {
+        return (factors, remaining);
+    }
+
+    for divisor in 2..limit {
+        let divisor = BigUint::from(divisor);
+        if &divisor * &divisor > remaining {
+            break;
+        }
+        tick();
+        let mut exponent = 0u32;
+        while (&remaining % &divisor).is_zero() {
+            remaining /= &divisor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((divisor, exponent));
+        }
+    }
+
+    (factors, remaining)
+}
+
+/// Computes the radical (square-free kernel) of a number, the product of its distinct prime factors.
+///
+/// The radical appears in the abc conjecture, where `rad(abc)` is compared against `c`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number whose radical is computed.
+///
+/// # Returns
+///
+/// The product of the distinct primes dividing `n`. Returns 1 for `n = 1` and 0 for `n = 0`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::radical;
+///
+/// assert_eq!(radical(&BigUint::from(72u32)), BigUint::from(6u32));
+/// ```
+#[cfg(feature = "std")]
+pub fn radical(n: &BigUint) -> BigUint {
+    if n.is_zero() {
+        return BigUint::zero();
+    }
+
+    factorize(n)
+        .into_iter()
+        .map(|(prime, _)| prime)
+        .product()
+}
+
+/// Checks whether a number is squarefree, that is, whether no prime divides it more than once.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to check.
+///
+/// # Returns
+///
+/// * `true` if every prime factor of `n` has exponent 1. This includes `n = 1`.
+/// * `false` if some prime factor appears with exponent 2 or more, or if `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_squarefree;
+///
+/// assert!(is_squarefree(&BigUint::from(30u32)));
+/// assert!(!is_squarefree(&BigUint::from(12u32)));
+/// ```
+#[cfg(feature = "std")]
+pub fn is_squarefree(n: &BigUint) -> bool {
+    if n.is_zero() {
+        return false;
+    }
+
+    factorize(n)
+        .iter()
+        .all(|(_, exponent)| *exponent == 1)
+}
+
+/// Counts the positive divisors of a number, `d(n)`.
+///
+/// From the factorization `n = Π p^e`, the number of divisors is `Π (e + 1)`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number whose divisors are counted.
+///
+/// # Returns
+///
+/// The number of divisors of `n`. Returns 0 for `n = 0`, which has no finite divisor count.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::num_divisors;
+///
+/// assert_eq!(num_divisors(&BigUint::from(12u32)), BigUint::from(6u32));
+/// ```
+#[cfg(feature = "std")]
+pub fn num_divisors(n: &BigUint) -> BigUint {
+    if n.is_zero() {
+        return BigUint::zero();
+    }
+
+    factorize(n)
+        .iter()
+        .map(|(_, exponent)| BigUint::from(exponent + 1))
+        .product()
+}
+
+/// Computes the sum of the positive divisors of a number, `σ(n)`.
+///
+/// From the factorization `n = Π p^e`, the sum of divisors is `Π (p^(e+1) - 1) / (p - 1)`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number whose divisors are summed.
+///
+/// # Returns
+///
+/// The sum of the divisors of `n`. Returns 0 for `n = 0`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::sum_divisors;
+///
+/// assert_eq!(sum_divisors(&BigUint::from(12u32)), BigUint::from(28u32));
+/// ```
+#[cfg(feature = "std")]
+pub fn sum_divisors(n: &BigUint) -> BigUint {
+    if n.is_zero() {
+        return BigUint::zero();
+    }
+
+    factorize(n)
+        .iter()
+        .map(|(p, exponent)| (pow(p, &BigUint::from(exponent + 1)) - BigUint::one()) / (p - BigUint::one()))
+        .product()
+}
+
+/// Computes the Jacobsthal function `j(n)` for small values of `n`.
+///
+/// `j(n)` is the smallest `m` such that every sequence of `m` consecutive integers contains one coprime
+/// to `n`. Coprimality to `n` only depends on its distinct prime factors, and repeats with period
+/// `rad(n)`, so `j(n)` is the largest gap between consecutive integers coprime to `n` within one period.
+/// The scan takes time proportional to `rad(n)`, which limits this function to small inputs.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number whose Jacobsthal function is computed.
+///
+/// # Returns
+///
+/// The value of `j(n)`. Returns 1 for `n = 1` and 0 for `n = 0`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::jacobsthal;
+///
+/// assert_eq!(jacobsthal(&BigUint::from(30u32)), BigUint::from(6u32));
+/// ```
+#[cfg(feature = "std")]
+pub fn jacobsthal(n: &BigUint) -> BigUint {
+    if n.is_zero() {
+        return BigUint::zero();
+    }
+
+    let primes: Vec<BigUint> = factorize(n)
+        .into_iter()
+        .map(|(prime, _)| prime)
+        .collect();
+    let period: BigUint = primes.iter().product();
+
+    // 1 and period + 1 are always coprime to n, so scanning between them covers a full period
+    let mut longest = BigUint::zero();
+    let mut previous = BigUint::one();
+    let mut k = BigUint::from(2u32);
+    while k <= &period + BigUint::one() {
+        if primes.iter().all(|p| &k % p != BigUint::zero()) {
+            longest = longest.max(&k - &previous);
+            previous = k.clone();
+        }
+        k += BigUint::one();
+    }
+
+    longest
+}
+
+/// Computes the prime signature of a number, the exponents of its factorization sorted in descending order.
+///
+/// Numbers with the same signature share the same multiplicative shape, and therefore the same number
+/// of divisors. For example, 12 = 2^2 * 3 and 18 = 2 * 3^2 both have signature `[2, 1]`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to classify.
+///
+/// # Returns
+///
+/// The exponents of the prime factors of `n`, largest first. Returns an empty vector for 0 and 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::prime_signature;
+///
+/// assert_eq!(prime_signature(&BigUint::from(12u32)), vec![2, 1]);
+/// assert_eq!(prime_signature(&BigUint::from(18u32)), vec![2, 1]);
+/// ```
+#[cfg(feature = "std")]
+pub fn prime_signature(n: &BigUint) -> Vec<u32> {
+    let mut exponents: Vec<u32> = factorize(n)
+        .into_iter()
+        .map(|(_, exponent)| exponent)
+        .collect();
+    exponents.sort_unstable_by(|a, b| b.cmp(a));
+    exponents
+}
+
+/// Merges duplicate primes in a factor list and sorts it ascending by prime.
+///
+/// Factorizers that split a number recursively can report the same prime several times, or out of order.
+/// Entries with the same prime are combined by adding their exponents.
+///
+/// # Arguments
+///
+/// * `factors` - A vector of `(prime, exponent)` pairs in any order, possibly with repeated primes.
+///
+/// # Returns
+///
+/// A vector of `(prime, exponent)` pairs with each prime appearing once, sorted ascending by prime.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::sort_and_dedup_factors;
+///
+/// let factors = vec![(BigUint::from(3u32), 1), (BigUint::from(2u32), 1), (BigUint::from(3u32), 1)];
+/// assert_eq!(sort_and_dedup_factors(factors), vec![(BigUint::from(2u32), 1), (BigUint::from(3u32), 2)]);
+/// ```
+pub fn sort_and_dedup_factors(factors: Vec<(BigUint, u32)>) -> Vec<(BigUint, u32)> {
+    let mut merged: BTreeMap<BigUint, u32> = BTreeMap::new();
+    for (prime, exponent) in factors {
+        *merged.entry(prime).or_insert(0) += exponent;
+    }
+    merged.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radicals() {
+        // Test case 1: 1
+        assert_eq!(radical(&BigUint::one()), BigUint::one());
+
+        // Test case 12: 2 * 3
+        assert_eq!(radical(&BigUint::from(12u32)), BigUint::from(6u32));
+
+        // Test case prime: itself
+        assert_eq!(radical(&BigUint::from(1000000007u32)), BigUint::from(1000000007u32));
+
+        // Test case 72: 2 * 3
+        assert_eq!(radical(&BigUint::from(72u32)), BigUint::from(6u32));
+    }
+
+    #[test]
+    fn squarefree() {
+        // Test case 0: False
+        assert_eq!(is_squarefree(&BigUint::zero()), false);
+
+        // Test case 1: True
+        assert_eq!(is_squarefree(&BigUint::one()), true);
+
+        // Test case 30: 2 * 3 * 5
+        assert_eq!(is_squarefree(&BigUint::from(30u32)), true);
+
+        // Test case 12: 2^2 * 3
+        assert_eq!(is_squarefree(&BigUint::from(12u32)), false);
+
+        // Test case prime squared: False
+        assert_eq!(is_squarefree(&BigUint::from(1018081u32)), false);
+    }
+
+    #[test]
+    fn divisors() {
+        // Test case 0: 0
+        assert_eq!(num_divisors(&BigUint::zero()), BigUint::zero());
+        assert_eq!(sum_divisors(&BigUint::zero()), BigUint::zero());
+
+        // Test case 1: 1
+        assert_eq!(num_divisors(&BigUint::one()), BigUint::one());
+        assert_eq!(sum_divisors(&BigUint::one()), BigUint::one());
+
+        // Test case 12: 1, 2, 3, 4, 6, 12
+        assert_eq!(num_divisors(&BigUint::from(12u32)), BigUint::from(6u32));
+        assert_eq!(sum_divisors(&BigUint::from(12u32)), BigUint::from(28u32));
+
+        // Test case perfect number 8128
+        assert_eq!(sum_divisors(&BigUint::from(8128u32)), BigUint::from(2u32 * 8128));
+    }
+
+    #[test]
+    fn jacobsthal_function() {
+        // Known values: j(1) = 1, j(2) = 2, j(6) = 4, j(30) = 6, j(210) = 10, j(2310) = 14
+        let known = [(1u32, 1u32), (2, 2), (6, 4), (30, 6), (210, 10), (2310, 14)];
+        for (n, j) in known {
+            assert_eq!(jacobsthal(&BigUint::from(n)), BigUint::from(j));
+        }
+
+        // Prime powers behave like the prime itself
+        assert_eq!(jacobsthal(&BigUint::from(8u32)), BigUint::from(2u32));
+        assert_eq!(jacobsthal(&BigUint::from(7u32)), BigUint::from(2u32));
+    }
+
+    #[test]
+    fn signatures() {
+        // Test case 1: Empty
+        assert_eq!(prime_signature(&BigUint::one()), Vec::<u32>::new());
+
+        // Test case primes: [1]
+        assert_eq!(prime_signature(&BigUint::from(2u32)), vec![1]);
+        assert_eq!(prime_signature(&BigUint::from(1000000007u32)), vec![1]);
+
+        // Test case squares of primes: [2]
+        assert_eq!(prime_signature(&BigUint::from(49u32)), vec![2]);
+        assert_eq!(prime_signature(&BigUint::from(1018081u32)), vec![2]);
+
+        // Test case semiprimes: [1, 1]
+        assert_eq!(prime_signature(&BigUint::from(15u32)), vec![1, 1]);
+        assert_eq!(prime_signature(&BigUint::from(914491u64 * 15959)), vec![1, 1]);
+
+        // Test case 12 and 18: [2, 1]
+        assert_eq!(prime_signature(&BigUint::from(12u32)), vec![2, 1]);
+        assert_eq!(prime_signature(&BigUint::from(18u32)), vec![2, 1]);
+
+        // Test case 360 = 2^3 * 3^2 * 5: [3, 2, 1]
+        assert_eq!(prime_signature(&BigUint::from(360u32)), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn sort_and_dedup() {
+        let to_big = |values: &[(u32, u32)]| -> Vec<(BigUint, u32)> {
+            values.iter().map(|&(p, e)| (BigUint::from(p), e)).collect()
+        };
+
+        assert_eq!(sort_and_dedup_factors(to_big(&[(3, 1), (2, 1), (3, 1)])), to_big(&[(2, 1), (3, 2)]));
+        assert_eq!(sort_and_dedup_factors(to_big(&[(7, 2), (5, 1), (7, 3), (2, 4)])), to_big(&[(2, 4), (5, 1), (7, 5)]));
+        assert_eq!(sort_and_dedup_factors(Vec::new()), Vec::new());
+
+        // Already merged and sorted lists are unchanged
+        let factors = factorize(&BigUint::from(360u32));
+        assert_eq!(sort_and_dedup_factors(factors.clone()), factors);
+    }
+
+    #[test]
+    fn strip_small() {
+        // Test case 2^3 * 3 * 1000003 below 10
+        let (factors, cofactor) = strip_small_factors(&BigUint::from(8u32 * 3 * 1000003), 10);
+        assert_eq!(factors, vec![(BigUint::from(2u32), 3), (BigUint::from(3u32), 1)]);
+        assert_eq!(cofactor, BigUint::from(1000003u32));
+
+        // Test case everything small: cofactor 1 or a prime
+        let (factors, cofactor) = strip_small_factors(&BigUint::from(360u32), 1000);
+        assert_eq!(factors, vec![(BigUint::from(2u32), 3), (BigUint::from(3u32), 2)]);
+        assert_eq!(cofactor, BigUint::from(5u32));
+        let (factors, cofactor) = strip_small_factors(&BigUint::from(1024u32), 1000);
+        assert_eq!(factors, vec![(BigUint::from(2u32), 10)]);
+        assert_eq!(cofactor, BigUint::one());
+
+        // Test case factors above the limit stay in the cofactor
+        let (factors, cofactor) = strip_small_factors(&BigUint::from(11u32 * 13 * 4), 10);
+        assert_eq!(factors, vec![(BigUint::from(2u32), 2)]);
+        assert_eq!(cofactor, BigUint::from(143u32));
+
+        // Test case 0, limit 0
+        assert_eq!(strip_small_factors(&BigUint::zero(), 10), (vec![], BigUint::zero()));
+        assert_eq!(strip_small_factors(&BigUint::from(12u32), 0), (vec![], BigUint::from(12u32)));
+    }
+}
@@ -1,5 +1,7 @@
-use num_bigint::BigUint;
-use num_traits::Zero;
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::{ BigInt, BigUint, Sign };
+use num_traits::{ One, Zero };
 
 /// Computes the greatest common divisor (GCD) of two `BigUint` numbers using the Euclidean algorithm.
 ///
@@ -39,14 +41,184 @@ pub fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
         a = temp;
     }
 
-    return a;
+    a
+}
+
+/// Computes the least common multiple (LCM) of two `BigUint` numbers.
+///
+/// The LCM is computed as `a / gcd(a, b) * b`, dividing before multiplying so that the intermediate value
+/// never exceeds the result.
+///
+/// # Arguments
+///
+/// * `a` - A reference to a `BigUint` representing the first number.
+/// * `b` - A reference to a `BigUint` representing the second number.
+///
+/// # Returns
+///
+/// The least common multiple of `a` and `b`. Returns 0 if either number is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::lcm;
+///
+/// assert_eq!(lcm(&BigUint::from(4u32), &BigUint::from(6u32)), BigUint::from(12u32));
+/// assert_eq!(lcm(&BigUint::from(0u32), &BigUint::from(5u32)), BigUint::from(0u32));
+/// ```
+pub fn lcm(a: &BigUint, b: &BigUint) -> BigUint {
+    // gcd(0, 0) is 0, so the division below needs both numbers nonzero
+    if a.is_zero() || b.is_zero() {
+        return BigUint::zero();
+    }
+
+    a / gcd(a, b) * b
+}
+
+/// Lists the `(a, b)` pairs the Euclidean algorithm goes through, for visualizing how `gcd` proceeds.
+///
+/// Each step replaces `(a, b)` with `(b, a mod b)`. The last pair has `b == 0`, and its `a` is the gcd.
+///
+/// # Arguments
+///
+/// * `a` - A reference to a `BigUint` representing the first number.
+/// * `b` - A reference to a `BigUint` representing the second number.
+///
+/// # Returns
+///
+/// A vector of `(a, b)` pairs, starting with the inputs and ending with the first pair whose `b` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::gcd_steps;
+///
+/// let steps = gcd_steps(&BigUint::from(10u32), &BigUint::from(4u32));
+/// assert_eq!(steps, vec![
+///     (BigUint::from(10u32), BigUint::from(4u32)),
+///     (BigUint::from(4u32), BigUint::from(2u32)),
+///     (BigUint::from(2u32), BigUint::from(0u32)),
+/// ]);
+/// ```
+pub fn gcd_steps(a: &BigUint, b: &BigUint) -> Vec<(BigUint, BigUint)> {
+    let mut a = a.clone();
+    let mut b = b.clone();
+    let mut steps = vec![(a.clone(), b.clone())];
+
+    while !b.is_zero() {
+        let remainder = &a % &b;
+        a = core::mem::replace(&mut b, remainder);
+        steps.push((a.clone(), b.clone()));
+    }
+
+    steps
+}
+
+/// Computes the greatest common divisor of two `BigUint` numbers together with Bézout coefficients.
+///
+/// The extended Euclidean algorithm carries the coefficients `x` and `y` along with the remainders, so that
+/// `a * x + b * y == g` holds at the end. The coefficients can be negative, so they are returned as `BigInt`.
+///
+/// # Arguments
+///
+/// * `a` - A reference to a `BigUint` representing the first number.
+/// * `b` - A reference to a `BigUint` representing the second number.
+///
+/// # Returns
+///
+/// A tuple `(g, x, y)` where `g` is the greatest common divisor of `a` and `b` and `a * x + b * y == g`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::{ BigInt, BigUint };
+/// use large_primes::extended_gcd;
+///
+/// let (g, x, y) = extended_gcd(&BigUint::from(240u32), &BigUint::from(46u32));
+/// assert_eq!(g, BigUint::from(2u32));
+/// assert_eq!(BigInt::from(240) * x + BigInt::from(46) * y, BigInt::from(2));
+/// ```
+pub fn extended_gcd(a: &BigUint, b: &BigUint) -> (BigUint, BigInt, BigInt) {
+    let mut old_r = BigInt::from_biguint(Sign::Plus, a.clone());
+    let mut r = BigInt::from_biguint(Sign::Plus, b.clone());
+    let mut old_x = BigInt::one();
+    let mut x = BigInt::zero();
+    let mut old_y = BigInt::zero();
+    let mut y = BigInt::one();
+
+    while !r.is_zero() {
+        let quotient = &old_r / &r;
+        let next_r = &old_r - &quotient * &r;
+        old_r = core::mem::replace(&mut r, next_r);
+        let next_x = &old_x - &quotient * &x;
+        old_x = core::mem::replace(&mut x, next_x);
+        let next_y = &old_y - &quotient * &y;
+        old_y = core::mem::replace(&mut y, next_y);
+    }
+
+    // The remainders never go negative, so the gcd converts back losslessly
+    (old_r.to_biguint().unwrap(), old_x, old_y)
+}
+
+/// Checks whether two `BigUint` numbers are coprime, that is, whether their greatest common divisor is 1.
+///
+/// # Arguments
+///
+/// * `a` - A reference to a `BigUint` representing the first number.
+/// * `b` - A reference to a `BigUint` representing the second number.
+///
+/// # Returns
+///
+/// `true` if `gcd(a, b) == 1`, `false` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_coprime;
+///
+/// assert!(is_coprime(&BigUint::from(8u32), &BigUint::from(15u32)));
+/// assert!(!is_coprime(&BigUint::from(6u32), &BigUint::from(15u32)));
+/// ```
+pub fn is_coprime(a: &BigUint, b: &BigUint) -> bool {
+    gcd(a, b) == BigUint::one()
+}
+
+/// Enumerates all coprime pairs `(a, b)` with `1 <= a < b <= n`.
+///
+/// Every pair is checked with `gcd`, which makes this a convenient way to exercise it over a whole range.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing the upper bound for both elements of the pair.
+///
+/// # Returns
+///
+/// A vector of pairs `(a, b)` with `gcd(a, b) == 1`, ordered by `a` and then by `b`.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::coprime_pairs_up_to;
+///
+/// assert_eq!(coprime_pairs_up_to(3), vec![(1, 2), (1, 3), (2, 3)]);
+/// ```
+pub fn coprime_pairs_up_to(n: u64) -> Vec<(u64, u64)> {
+    let mut pairs: Vec<(u64, u64)> = Vec::new();
+    for a in 1..=n {
+        for b in a + 1..=n {
+            if is_coprime(&BigUint::from(a), &BigUint::from(b)) {
+                pairs.push((a, b));
+            }
+        }
+    }
+    pairs
 }
 
 #[cfg(test)]
 mod tests {
-    use num_bigint::BigUint;
-    use num_traits::{ One, Zero };
-
     use super::*;
 
     #[test]
@@ -106,4 +278,73 @@ mod tests {
         assert_eq!(gcd(&BigUint::from(123456u32), &BigUint::from(123464u32)), BigUint::from(8u32));
         assert_eq!(gcd(&BigUint::from(123456u32), &BigUint::from(123465u32)), BigUint::from(3u32));
     }
+
+    #[test]
+    fn lcm_cases() {
+        // Edge cases with 0 and 1
+        assert_eq!(lcm(&BigUint::zero(), &BigUint::from(5u32)), BigUint::zero());
+        assert_eq!(lcm(&BigUint::from(5u32), &BigUint::zero()), BigUint::zero());
+        assert_eq!(lcm(&BigUint::zero(), &BigUint::zero()), BigUint::zero());
+        assert_eq!(lcm(&BigUint::one(), &BigUint::from(7u32)), BigUint::from(7u32));
+
+        // Small numbers
+        assert_eq!(lcm(&BigUint::from(4u32), &BigUint::from(6u32)), BigUint::from(12u32));
+        assert_eq!(lcm(&BigUint::from(21u32), &BigUint::from(6u32)), BigUint::from(42u32));
+
+        // Big Numbers 6-8 digits
+        assert_eq!(lcm(&BigUint::from(123456u32), &BigUint::from(123456u32)), BigUint::from(123456u32));
+        assert_eq!(lcm(&BigUint::from(123456u32), &BigUint::from(123457u32)), BigUint::from(15241507392u64));
+        assert_eq!(lcm(&BigUint::from(123456u32), &BigUint::from(123458u32)), BigUint::from(7620815424u64));
+        assert_eq!(lcm(&BigUint::from(123456u32), &BigUint::from(123459u32)), BigUint::from(5080584768u64));
+        assert_eq!(lcm(&BigUint::from(123456u32), &BigUint::from(123460u32)), BigUint::from(3810469440u64));
+        assert_eq!(lcm(&BigUint::from(123456u32), &BigUint::from(123462u32)), BigUint::from(2540354112u64));
+        assert_eq!(lcm(&BigUint::from(123456u32), &BigUint::from(123464u32)), BigUint::from(1905296448u64));
+
+        // gcd * lcm == a * b
+        let a = BigUint::from(123456u32);
+        let b = BigUint::from(123465u32);
+        assert_eq!(gcd(&a, &b) * lcm(&a, &b), &a * &b);
+    }
+
+    #[test]
+    fn coprime_pairs() {
+        assert_eq!(coprime_pairs_up_to(0), vec![]);
+        assert_eq!(coprime_pairs_up_to(1), vec![]);
+        assert_eq!(coprime_pairs_up_to(4), vec![(1, 2), (1, 3), (1, 4), (2, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn coprime() {
+        assert_eq!(is_coprime(&BigUint::one(), &BigUint::zero()), true);
+        assert_eq!(is_coprime(&BigUint::zero(), &BigUint::zero()), false);
+        assert_eq!(is_coprime(&BigUint::from(123456u32), &BigUint::from(123457u32)), true);
+        assert_eq!(is_coprime(&BigUint::from(123456u32), &BigUint::from(123458u32)), false);
+    }
+
+    #[test]
+    fn bezout_identity() {
+        let pairs = [(240u32, 46u32), (46, 240), (17, 5), (123456, 123457), (123456, 123462), (0, 7), (7, 0), (0, 0), (12, 12)];
+
+        for (a, b) in pairs {
+            let (g, x, y) = extended_gcd(&BigUint::from(a), &BigUint::from(b));
+            assert_eq!(g, gcd(&BigUint::from(a), &BigUint::from(b)));
+            assert_eq!(BigInt::from(a) * x + BigInt::from(b) * y, BigInt::from_biguint(Sign::Plus, g));
+        }
+    }
+
+    #[test]
+    fn euclid_steps() {
+        let expected: Vec<(BigUint, BigUint)> = [(48u32, 18u32), (18, 12), (12, 6), (6, 0)]
+            .iter()
+            .map(|&(a, b)| (BigUint::from(a), BigUint::from(b)))
+            .collect();
+        assert_eq!(gcd_steps(&BigUint::from(48u32), &BigUint::from(18u32)), expected);
+
+        // A smaller first argument costs one extra swap step
+        let steps = gcd_steps(&BigUint::from(18u32), &BigUint::from(48u32));
+        assert_eq!(steps[1], (BigUint::from(48u32), BigUint::from(18u32)));
+        assert_eq!(steps.len(), 5);
+
+        assert_eq!(gcd_steps(&BigUint::from(7u32), &BigUint::zero()), vec![(BigUint::from(7u32), BigUint::zero())]);
+    }
 }
@@ -0,0 +1,96 @@
+use num_bigint::{ BigInt, BigUint, Sign };
+use num_traits::{ One, Zero };
+
+/// Computes the modular multiplicative inverse of `a` modulo `modulus`.
+///
+/// The inverse is found with the extended Euclidean algorithm, which tracks a Bézout coefficient `x`
+/// such that `a * x ≡ gcd(a, modulus) (mod modulus)`. The coefficient may go negative along the way,
+/// so it is tracked as a `BigInt` and reduced back into `[0, modulus)` at the end.
+///
+/// # Arguments
+///
+/// * `a` - A reference to a `BigUint` representing the number to invert.
+/// * `modulus` - A reference to a `BigUint` representing the modulus.
+///
+/// # Returns
+///
+/// * `Some(inverse)` with `a * inverse ≡ 1 (mod modulus)` and `inverse < modulus`.
+/// * `None` if `gcd(a, modulus) != 1`, in which case no inverse exists, or if `modulus` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::mod_inverse;
+///
+/// assert_eq!(mod_inverse(&BigUint::from(3u32), &BigUint::from(11u32)), Some(BigUint::from(4u32)));
+/// assert_eq!(mod_inverse(&BigUint::from(2u32), &BigUint::from(4u32)), None);
+/// ```
+pub fn mod_inverse(a: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    if modulus.is_zero() {
+        return None;
+    }
+
+    let mut old_r = BigInt::from_biguint(Sign::Plus, a % modulus);
+    let mut r = BigInt::from_biguint(Sign::Plus, modulus.clone());
+    let mut old_x = BigInt::one();
+    let mut x = BigInt::zero();
+
+    while !r.is_zero() {
+        let quotient = &old_r / &r;
+        let next_r = &old_r - &quotient * &r;
+        old_r = core::mem::replace(&mut r, next_r);
+        let next_x = &old_x - &quotient * &x;
+        old_x = core::mem::replace(&mut x, next_x);
+    }
+
+    if !old_r.is_one() {
+        // With modulus 1 every number is congruent to 0, which is its own inverse
+        return if modulus.is_one() { Some(BigUint::zero()) } else { None };
+    }
+
+    let modulus = BigInt::from_biguint(Sign::Plus, modulus.clone());
+    let inverse = ((old_x % &modulus) + &modulus) % &modulus;
+    inverse.to_biguint()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 3^-1 mod 11: 4
+        assert_eq!(mod_inverse(&BigUint::from(3u32), &BigUint::from(11u32)), Some(BigUint::from(4u32)));
+
+        // Test case 2^-1 mod 4: None
+        assert_eq!(mod_inverse(&BigUint::from(2u32), &BigUint::from(4u32)), None);
+    }
+
+    #[test]
+    fn inverses() {
+        // Every nonzero residue of a prime modulus has an inverse
+        let p = BigUint::from(101u32);
+        for a in 1..101u32 {
+            let a = BigUint::from(a);
+            let inverse = mod_inverse(&a, &p).unwrap();
+            assert!(inverse < p);
+            assert_eq!((&a * &inverse) % &p, BigUint::one());
+        }
+
+        // Residues sharing a factor with a composite modulus have none
+        let n = BigUint::from(30u32);
+        for a in 0..60u32 {
+            let a = BigUint::from(a);
+            let coprime = crate::operations::gcd(&a, &n).is_one();
+            assert_eq!(mod_inverse(&a, &n).is_some(), coprime);
+        }
+
+        // RSA-style exponent inversion
+        let phi = BigUint::from(3120u32);
+        assert_eq!(mod_inverse(&BigUint::from(17u32), &phi), Some(BigUint::from(2753u32)));
+
+        assert_eq!(mod_inverse(&BigUint::from(5u32), &BigUint::one()), Some(BigUint::zero()));
+        assert_eq!(mod_inverse(&BigUint::from(5u32), &BigUint::zero()), None);
+    }
+}
@@ -0,0 +1,122 @@
+use num_bigint::BigUint;
+use num_traits::{ One, Zero };
+
+/// Computes the Jacobi symbol `(a/n)` of two `BigUint` numbers.
+///
+/// The Jacobi symbol generalizes the Legendre symbol to any odd positive modulus `n`. It is computed
+/// without factoring `n` by repeatedly pulling out factors of two and flipping the sign according to
+/// the law of quadratic reciprocity. Since `BigUint` has no sign, the result is tracked with an `i8`.
+///
+/// # Arguments
+///
+/// * `a` - A reference to a `BigUint` representing the numerator.
+/// * `n` - A reference to a `BigUint` representing the modulus. It must be odd.
+///
+/// # Returns
+///
+/// * `1` or `-1` if `a` is coprime to `n`, according to the symbol.
+/// * `0` if `a` and `n` share a common factor, or if `n` is even (where the symbol is undefined).
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::jacobi;
+///
+/// assert_eq!(jacobi(&BigUint::from(5u32), &BigUint::from(21u32)), 1);
+/// assert_eq!(jacobi(&BigUint::from(2u32), &BigUint::from(7u32)), 1);
+/// assert_eq!(jacobi(&BigUint::from(3u32), &BigUint::from(7u32)), -1);
+/// ```
+pub fn jacobi(a: &BigUint, n: &BigUint) -> i8 {
+    let zero = BigUint::zero();
+    let one = BigUint::one();
+
+    if n % 2u32 == zero {
+        return 0;
+    }
+
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1i8;
+
+    while a != zero {
+        while &a % 2u32 == zero {
+            a >>= 1u32;
+            let r = &n % 8u32;
+            if r == BigUint::from(3u32) || r == BigUint::from(5u32) {
+                result = -result;
+            }
+        }
+
+        core::mem::swap(&mut a, &mut n);
+        if &a % 4u32 == BigUint::from(3u32) && &n % 4u32 == BigUint::from(3u32) {
+            result = -result;
+        }
+        a %= &n;
+    }
+
+    if n == one {
+        result
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case (0/1): 1
+        assert_eq!(jacobi(&BigUint::zero(), &BigUint::one()), 1);
+
+        // Test case (3/3): 0
+        assert_eq!(jacobi(&BigUint::from(3u32), &BigUint::from(3u32)), 0);
+
+        // Test case (1/4): 0, undefined for even modulus
+        assert_eq!(jacobi(&BigUint::one(), &BigUint::from(4u32)), 0);
+    }
+
+    #[test]
+    fn known_values() {
+        assert_eq!(jacobi(&BigUint::from(5u32), &BigUint::from(21u32)), 1);
+        assert_eq!(jacobi(&BigUint::from(2u32), &BigUint::from(15u32)), 1);
+        assert_eq!(jacobi(&BigUint::from(7u32), &BigUint::from(15u32)), -1);
+        assert_eq!(jacobi(&BigUint::from(1001u32), &BigUint::from(9907u32)), -1);
+        assert_eq!(jacobi(&BigUint::from(19u32), &BigUint::from(45u32)), 1);
+        assert_eq!(jacobi(&BigUint::from(6u32), &BigUint::from(21u32)), 0);
+    }
+
+    #[test]
+    fn euler_criterion() {
+        // For an odd prime p, (a/p) ≡ a^((p-1)/2) (mod p)
+        for p in [3u32, 5, 7, 11, 13, 17, 19, 23, 97] {
+            let modulus = BigUint::from(p);
+            for a in 0..2 * p {
+                let a = BigUint::from(a);
+                let euler = crate::operations::pow_mod(&a, &BigUint::from((p - 1) / 2), &modulus);
+                let expected = if euler.is_zero() {
+                    0
+                } else if euler.is_one() {
+                    1
+                } else {
+                    -1
+                };
+                assert_eq!(jacobi(&a, &modulus), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn multiplicative_in_modulus() {
+        // (a/mn) = (a/m)(a/n)
+        for (m, n) in [(3u32, 5u32), (7, 9), (11, 21), (15, 17)] {
+            for a in 0..50u32 {
+                let a = BigUint::from(a);
+                let product = jacobi(&a, &BigUint::from(m)) * jacobi(&a, &BigUint::from(n));
+                assert_eq!(jacobi(&a, &BigUint::from(m * n)), product);
+            }
+        }
+    }
+}
@@ -1,7 +1,24 @@
 pub mod pow;
 pub mod gcd;
 pub mod utils;
+pub mod jacobi;
+pub mod totient;
+pub mod factor;
+pub mod inverse;
 
 pub use pow::pow;
 pub use pow::pow_mod;
-pub use gcd::gcd;
+pub use pow::pow_mod_many;
+pub use pow::pow_mod_trace;
+pub use pow::is_perfect_power;
+pub use gcd::{ gcd, lcm, gcd_steps, extended_gcd, is_coprime, coprime_pairs_up_to };
+pub use jacobi::jacobi;
+pub use inverse::mod_inverse;
+pub use totient::euler_totient;
+#[cfg(feature = "std")]
+pub use totient::totient_sum;
+pub use factor::{ strip_small_factors, sort_and_dedup_factors };
+#[cfg(feature = "std")]
+pub(crate) use factor::strip_small_factors_with_progress;
+#[cfg(feature = "std")]
+pub use factor::{ radical, is_squarefree, num_divisors, sum_divisors, jacobsthal, prime_signature };
@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
 use num_bigint::BigUint;
 use num_traits::{ One, Zero };
+use super::utils::isqrt;
 
 /// Computes the power of a `BigUint` base raised to a `BigUint` exponent.
 ///
@@ -38,7 +40,7 @@ pub fn pow(base: &BigUint, exp: &BigUint) -> BigUint {
 
     let mut power = exp.clone();
 
-    while &power > &zero {
+    while power > zero {
         if &power % &two == one {
             result = &result * &current_base;
         }
@@ -90,7 +92,7 @@ pub fn pow_mod(base: &BigUint, exp: &BigUint, modulus: &BigUint) -> BigUint {
 
     let mut power = exp.clone();
 
-    while &power > &zero {
+    while power > zero {
         if &power % &two == one {
             result = (&result * &base) % modulus;
         }
@@ -103,6 +105,173 @@ pub fn pow_mod(base: &BigUint, exp: &BigUint, modulus: &BigUint) -> BigUint {
     result
 }
 
+/// Computes the modular exponentiation like `pow_mod`, also returning the intermediate results.
+///
+/// The bits of `exp` are processed from the least significant one, as in `pow_mod`. After each bit the
+/// running `result` is recorded, whether or not the bit multiplied the current power of `base` into it.
+///
+/// # Arguments
+///
+/// * `base` - A reference to a `BigUint` representing the base.
+/// * `exp` - A reference to a `BigUint` representing the exponent.
+/// * `modulus` - A reference to a `BigUint` representing the modulus.
+///
+/// # Returns
+///
+/// A tuple of `(base ^ exp) % modulus` and the vector of intermediate results, one per bit of `exp`. The
+/// last intermediate result is the final result, and the vector is empty when `exp` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::pow_mod_trace;
+///
+/// // 13 = 0b1101: 4, then 4 (bit 1 is 0), then 4 * 4^4 mod 497, then that times 4^8 mod 497
+/// let (result, trace) = pow_mod_trace(&BigUint::from(4u32), &BigUint::from(13u32), &BigUint::from(497u32));
+/// assert_eq!(result, BigUint::from(445u32));
+/// assert_eq!(trace, vec![BigUint::from(4u32), BigUint::from(4u32), BigUint::from(30u32), BigUint::from(445u32)]);
+/// ```
+pub fn pow_mod_trace(base: &BigUint, exp: &BigUint, modulus: &BigUint) -> (BigUint, Vec<BigUint>) {
+    let mut result = BigUint::one();
+    let mut base = base % modulus;
+    let mut trace: Vec<BigUint> = Vec::with_capacity(exp.bits() as usize);
+
+    for i in 0..exp.bits() {
+        if exp.bit(i) {
+            result = (&result * &base) % modulus;
+        }
+        trace.push(result.clone());
+        base = (&base * &base) % modulus;
+    }
+
+    (result, trace)
+}
+
+/// Computes the modular exponentiation of many `BigUint` bases sharing the same exponent and modulus.
+///
+/// The bits of `exp` are extracted once and reused for every base, which saves work when verifying many
+/// values at once. With the `parallel` feature enabled, the bases are processed on multiple threads.
+///
+/// # Arguments
+///
+/// * `bases` - A slice of `BigUint` values to be raised to the power.
+/// * `exp` - A reference to a `BigUint` representing the shared exponent.
+/// * `modulus` - A reference to a `BigUint` representing the shared modulus.
+///
+/// # Returns
+///
+/// A vector holding `(base ^ exp) % modulus` for each base, in the same order as `bases`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::pow_mod_many;
+///
+/// let bases = vec![BigUint::from(2u32), BigUint::from(3u32)];
+/// let results = pow_mod_many(&bases, &BigUint::from(10u32), &BigUint::from(1000u32));
+/// assert_eq!(results, vec![BigUint::from(24u32), BigUint::from(49u32)]);
+/// ```
+pub fn pow_mod_many(bases: &[BigUint], exp: &BigUint, modulus: &BigUint) -> Vec<BigUint> {
+    // Bits of the exponent, least significant first
+    let bits: Vec<bool> = (0..exp.bits()).map(|i| exp.bit(i)).collect();
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        bases
+            .par_iter()
+            .map(|base| pow_mod_bits(base, &bits, modulus))
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        bases
+            .iter()
+            .map(|base| pow_mod_bits(base, &bits, modulus))
+            .collect()
+    }
+}
+
+fn pow_mod_bits(base: &BigUint, bits: &[bool], modulus: &BigUint) -> BigUint {
+    let mut result = BigUint::one();
+    let mut base = base % modulus;
+
+    for bit in bits {
+        if *bit {
+            result = (&result * &base) % modulus;
+        }
+        base = (&base * &base) % modulus;
+    }
+
+    result
+}
+
+/// Checks whether a number is a perfect power, `n = base^k` with `k >= 2`.
+///
+/// Each exponent `k` from `log2(n)` down to 2 is tried. The base is found by binary search on `base^k`,
+/// except for squares, which `isqrt` settles directly. Trying the largest exponent first returns the smallest possible base, so 1024 gives
+/// `(2, 10)` rather than `(32, 2)`. This is the first step of the AKS primality test.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to check.
+///
+/// # Returns
+///
+/// * `Some((base, k))` with `base^k == n` and `k >= 2`, using the smallest possible base.
+/// * `None` if `n` is not a perfect power, or if `n` is 0 or 1, which are powers of themselves for every `k`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_perfect_power;
+///
+/// assert_eq!(is_perfect_power(&BigUint::from(1024u32)), Some((BigUint::from(2u32), 10)));
+/// assert_eq!(is_perfect_power(&BigUint::from(36u32)), Some((BigUint::from(6u32), 2)));
+/// assert_eq!(is_perfect_power(&BigUint::from(97u32)), None);
+/// ```
+pub fn is_perfect_power(n: &BigUint) -> Option<(BigUint, u32)> {
+    if *n <= BigUint::one() {
+        return None;
+    }
+
+    // base >= 2 means base^k >= 2^k, so k is at most log2(n)
+    let max_exponent = (n.bits() - 1) as u32;
+    for k in (2..=max_exponent).rev() {
+        if k == 2 {
+            let root = isqrt(n);
+            if &root * &root == *n {
+                return Some((root, 2));
+            }
+            continue;
+        }
+
+        // The base of a k-th power lies in [2, 2^ceil(bits / k)]
+        let mut low = BigUint::from(2u32);
+        let mut high = BigUint::one() << n.bits().div_ceil(k as u64);
+        while low <= high {
+            let mid: BigUint = (&low + &high) >> 1u32;
+            match mid.pow(k).cmp(n) {
+                core::cmp::Ordering::Equal => {
+                    return Some((mid, k));
+                }
+                core::cmp::Ordering::Less => {
+                    low = mid + BigUint::one();
+                }
+                core::cmp::Ordering::Greater => {
+                    high = mid - BigUint::one();
+                }
+            }
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +344,62 @@ mod tests {
 
         assert_eq!(pow_mod(&base, &exp, &modulus), BigUint::from(9376u32));
     }
+
+    #[test]
+    fn many_bases() {
+        let bases: Vec<BigUint> = [2u32, 3, 5].iter().map(|x| BigUint::from(*x)).collect();
+        let exp = BigUint::from(100u32);
+        let modulus = BigUint::from(1000u32);
+
+        let expected: Vec<BigUint> = bases
+            .iter()
+            .map(|base| pow_mod(base, &exp, &modulus))
+            .collect();
+
+        assert_eq!(pow_mod_many(&bases, &exp, &modulus), expected);
+        assert_eq!(pow_mod_many(&[], &exp, &modulus), Vec::<BigUint>::new());
+    }
+
+    #[test]
+    fn perfect_powers() {
+        // Test case 1024: 2^10 or 32^2
+        let (base, k) = is_perfect_power(&BigUint::from(1024u32)).unwrap();
+        assert_eq!(pow(&base, &BigUint::from(k)), BigUint::from(1024u32));
+        assert!(k >= 2);
+        assert_eq!((base, k), (BigUint::from(2u32), 10));
+
+        // Test case non-prime-power powers
+        assert_eq!(is_perfect_power(&BigUint::from(216u32)), Some((BigUint::from(6u32), 3)));
+        assert_eq!(is_perfect_power(&BigUint::from(4u32)), Some((BigUint::from(2u32), 2)));
+
+        // Test case primes and other non-powers: None
+        for n in [2u32, 3, 5, 97, 1000000007, 12, 1001] {
+            assert_eq!(is_perfect_power(&BigUint::from(n)), None);
+        }
+        assert_eq!(is_perfect_power(&BigUint::zero()), None);
+        assert_eq!(is_perfect_power(&BigUint::one()), None);
+
+        // Test case large power: 1000003^7
+        let base = BigUint::from(1000003u32);
+        assert_eq!(is_perfect_power(&base.pow(7)), Some((base.clone(), 7)));
+        assert_eq!(is_perfect_power(&(base.pow(7) + BigUint::one())), None);
+    }
+
+    #[test]
+    fn trace() {
+        let modulus = BigUint::from(1000000007u32);
+        for (base, exp) in [(2u32, 1u32), (3, 1000), (12345, 65537), (7, 1 << 20)] {
+            let base = BigUint::from(base);
+            let exp = BigUint::from(exp);
+            let (result, trace) = pow_mod_trace(&base, &exp, &modulus);
+            assert_eq!(trace.len() as u64, exp.bits());
+            assert_eq!(result, pow_mod(&base, &exp, &modulus));
+            assert_eq!(trace.last(), Some(&result));
+        }
+
+        // Test case exponent 0: empty trace
+        let (result, trace) = pow_mod_trace(&BigUint::from(5u32), &BigUint::zero(), &modulus);
+        assert_eq!(result, BigUint::one());
+        assert_eq!(trace, Vec::<BigUint>::new());
+    }
 }
@@ -0,0 +1,111 @@
+use num_bigint::BigUint;
+use num_traits::{ One, Zero };
+#[cfg(feature = "std")]
+use crate::generators::linear_sieve;
+
+/// Computes the summatory totient function, the sum of Euler's totient `φ(k)` for all `1 <= k <= n`.
+///
+/// All totient values are computed at once with `linear_sieve`, so the whole computation runs in `O(n)`
+/// without factoring each number.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing the upper bound of the sum.
+///
+/// # Returns
+///
+/// The sum `φ(1) + φ(2) + ... + φ(n)`. Returns zero if `n` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::totient_sum;
+///
+/// assert_eq!(totient_sum(5), BigUint::from(10u32));
+/// ```
+#[cfg(feature = "std")]
+pub fn totient_sum(n: u64) -> BigUint {
+    let (_, totients) = linear_sieve(n);
+    totients.iter().map(|x| BigUint::from(*x)).sum()
+}
+
+/// Computes Euler's totient `φ(n)`, the count of integers in `[1, n]` coprime to `n`.
+///
+/// The distinct prime factors of `n` are found by trial division, and `φ(n) = n * Π (1 - 1/p)`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number whose totient is computed.
+///
+/// # Returns
+///
+/// The value of `φ(n)`. Returns zero if `n` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::euler_totient;
+///
+/// assert_eq!(euler_totient(&BigUint::from(36u32)), BigUint::from(12u32));
+/// ```
+pub fn euler_totient(n: &BigUint) -> BigUint {
+    let mut result = n.clone();
+    let mut remaining = n.clone();
+
+    let mut factor = BigUint::from(2u32);
+    while &factor * &factor <= remaining {
+        if &remaining % &factor == BigUint::zero() {
+            while &remaining % &factor == BigUint::zero() {
+                remaining /= &factor;
+            }
+            result -= &result / &factor;
+        }
+        factor += BigUint::one();
+    }
+    if remaining > BigUint::one() {
+        result -= &result / &remaining;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0: 0
+        assert_eq!(totient_sum(0), BigUint::from(0u32));
+
+        // Test case 1: 1
+        assert_eq!(totient_sum(1), BigUint::from(1u32));
+
+        // Test case 5: 1 + 1 + 2 + 2 + 4
+        assert_eq!(totient_sum(5), BigUint::from(10u32));
+    }
+
+    #[test]
+    fn general_cases() {
+        // Test case 10: 1 + 1 + 2 + 2 + 4 + 2 + 6 + 4 + 6 + 4
+        assert_eq!(totient_sum(10), BigUint::from(32u32));
+
+        // Test case 1000: known value of the summatory totient
+        assert_eq!(totient_sum(1000), BigUint::from(304192u32));
+    }
+
+    #[test]
+    fn totient() {
+        // Test case 0, 1: 0, 1
+        assert_eq!(euler_totient(&BigUint::zero()), BigUint::zero());
+        assert_eq!(euler_totient(&BigUint::one()), BigUint::one());
+
+        // Test case prime: p - 1
+        assert_eq!(euler_totient(&BigUint::from(1000000007u32)), BigUint::from(1000000006u32));
+
+        // Test case 2^10: 2^9
+        assert_eq!(euler_totient(&BigUint::from(1024u32)), BigUint::from(512u32));
+    }
+}
@@ -1,6 +1,11 @@
+use alloc::vec;
 use num_bigint::BigUint;
 use num_traits::One;
 use num_traits::Zero;
+use num_integer::Integer;
+use rand::RngCore;
+use super::inverse::mod_inverse;
+use crate::primality::miller_rabin;
 
 /// Counts the number of trailing zeros in the binary representation of a `BigUint`.
 ///
@@ -32,9 +37,584 @@ pub fn get_trailing_zeros(num: &BigUint) -> BigUint {
     let mut num_copy = num.clone(); // Clone the number to work with
 
     while (&num_copy & &BigUint::one()) == BigUint::zero() {
-        num_copy = num_copy >> 1u32; // Right shift to divide by 2
+        num_copy >>= 1u32; // Right shift to divide by 2
         trailing_zeros += BigUint::one();
     }
 
     trailing_zeros
 }
+
+/// Quickly classifies numbers that are trivially prime or composite.
+///
+/// This function is meant to be called before a heavier primality test. It settles numbers less than 2,
+/// the small primes 2, 3 and 5, and any multiple of 2, 3 or 5, leaving everything else to the caller.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to classify.
+///
+/// # Returns
+///
+/// * `Some(true)` if `num` is 2, 3 or 5.
+/// * `Some(false)` if `num` is less than 2 or divisible by 2, 3 or 5.
+/// * `None` if `num` needs a full primality test.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::quick_composite_check;
+///
+/// assert_eq!(quick_composite_check(&BigUint::from(3u32)), Some(true));
+/// assert_eq!(quick_composite_check(&BigUint::from(25u32)), Some(false));
+/// assert_eq!(quick_composite_check(&BigUint::from(49u32)), None);
+/// ```
+pub fn quick_composite_check(num: &BigUint) -> Option<bool> {
+    if *num < BigUint::from(2u32) {
+        return Some(false);
+    }
+
+    for p in [2u32, 3, 5] {
+        let p = BigUint::from(p);
+        if *num == p {
+            return Some(true);
+        }
+        if num % &p == BigUint::zero() {
+            return Some(false);
+        }
+    }
+
+    None
+}
+
+/// Draws a base uniformly at random from `[2, n - 2]`, as used by randomized primality tests.
+///
+/// A random number with as many bits as the size of the range is drawn, and rejected if it falls outside
+/// the range. Since the range covers more than half of the candidates, fewer than two draws are needed on
+/// average.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number being tested. It must be at least 4.
+/// * `rng` - The random number generator to draw from.
+///
+/// # Returns
+///
+/// A `BigUint` uniformly distributed in `[2, n - 2]`.
+///
+/// # Panics
+///
+/// Panics if `n` is less than 4, since the range is then empty.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+/// use large_primes::utils::random_base;
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let n = BigUint::from(101u32);
+/// let base = random_base(&n, &mut rng);
+/// assert!(base >= BigUint::from(2u32) && base <= BigUint::from(99u32));
+/// ```
+pub fn random_base(n: &BigUint, rng: &mut impl RngCore) -> BigUint {
+    assert!(*n >= BigUint::from(4u32), "random_base requires n >= 4");
+
+    // Number of values in [2, n - 2]
+    let span = n - BigUint::from(3u32);
+    let bits = span.bits();
+    let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+
+    loop {
+        rng.fill_bytes(&mut bytes);
+        let candidate = BigUint::from_bytes_le(&bytes) >> (bytes.len() as u64 * 8 - bits);
+        if candidate < span {
+            return candidate + BigUint::from(2u32);
+        }
+    }
+}
+
+/// Computes `k! mod modulus` by multiplying the factors one at a time and reducing after each step.
+///
+/// When `k >= modulus`, the modulus itself appears among the factors, so the result is zero without
+/// any multiplication.
+///
+/// # Arguments
+///
+/// * `k` - A reference to a `BigUint` representing the number whose factorial is computed.
+/// * `modulus` - A reference to a `BigUint` representing the modulus. It must be nonzero.
+///
+/// # Returns
+///
+/// The value of `k! mod modulus`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::modular_factorial;
+///
+/// // Wilson's theorem: (p - 1)! ≡ -1 (mod p)
+/// assert_eq!(modular_factorial(&BigUint::from(6u32), &BigUint::from(7u32)), BigUint::from(6u32));
+/// ```
+pub fn modular_factorial(k: &BigUint, modulus: &BigUint) -> BigUint {
+    if k >= modulus {
+        return BigUint::zero();
+    }
+
+    let mut result = BigUint::one() % modulus;
+    let mut factor = BigUint::from(2u32);
+    while &factor <= k {
+        result = (result * &factor) % modulus;
+        factor += BigUint::one();
+    }
+    result
+}
+
+/// Computes the binomial coefficient `C(n, k) mod p` for a prime `p` using Lucas' theorem.
+///
+/// Lucas' theorem states that `C(n, k) ≡ Π C(n_i, k_i) (mod p)`, where `n_i` and `k_i` are the digits of
+/// `n` and `k` in base `p`. Each small binomial is computed as a product of `k_i` factors, divided with
+/// `mod_inverse`, so `n` and `k` may be far too large for the coefficient to be computed directly.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the size of the set.
+/// * `k` - A reference to a `BigUint` representing the size of the chosen subset.
+/// * `p` - A reference to a `BigUint` representing the modulus. It must be prime.
+///
+/// # Returns
+///
+/// The value of `C(n, k) mod p`, which is 0 when `k > n`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::binomial_mod_prime;
+///
+/// // C(10, 3) = 120 ≡ 1 (mod 7)
+/// let result = binomial_mod_prime(&BigUint::from(10u32), &BigUint::from(3u32), &BigUint::from(7u32));
+/// assert_eq!(result, BigUint::from(1u32));
+/// ```
+pub fn binomial_mod_prime(n: &BigUint, k: &BigUint, p: &BigUint) -> BigUint {
+    let mut n = n.clone();
+    let mut k = k.clone();
+    let mut result = BigUint::one() % p;
+
+    while !k.is_zero() {
+        let n_digit = &n % p;
+        let k_digit = &k % p;
+        if k_digit > n_digit {
+            return BigUint::zero();
+        }
+
+        // C(n_digit, k_digit) = Π (n_digit - j) / (j + 1) for j in [0, k_digit)
+        let mut numerator = BigUint::one();
+        let mut denominator = BigUint::one();
+        let mut j = BigUint::zero();
+        while j < k_digit {
+            numerator = (numerator * (&n_digit - &j)) % p;
+            j += BigUint::one();
+            denominator = (denominator * &j) % p;
+        }
+        // The denominator is a product of numbers below p, hence invertible
+        let inverse = mod_inverse(&denominator, p).unwrap();
+        result = (result * numerator * inverse) % p;
+
+        n /= p;
+        k /= p;
+    }
+
+    result
+}
+
+/// Computes the floor of the square root of a `BigUint`.
+///
+/// This is the square root used throughout the crate: the result `r` is the largest integer with
+/// `r * r <= n`, so `isqrt(0) == 0` and `isqrt(1) == 1`.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` whose square root is computed.
+///
+/// # Returns
+///
+/// The floor of the square root of `n`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::isqrt;
+///
+/// assert_eq!(isqrt(&BigUint::from(15u32)), BigUint::from(3u32));
+/// assert_eq!(isqrt(&BigUint::from(16u32)), BigUint::from(4u32));
+/// ```
+pub fn isqrt(n: &BigUint) -> BigUint {
+    n.sqrt()
+}
+
+/// Divides two `BigUint` values, returning both the quotient and the remainder.
+///
+/// This wraps `num_integer::Integer::div_rem`, so `a == q * b + r` with `r < b`. The divisor must be nonzero:
+/// instead of panicking, a zero divisor returns `(0, 0)`, which is not a meaningful result.
+///
+/// # Arguments
+///
+/// * `a` - A reference to a `BigUint` representing the dividend.
+/// * `b` - A reference to a `BigUint` representing the divisor. It must be nonzero.
+///
+/// # Returns
+///
+/// A tuple `(quotient, remainder)`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::div_rem;
+///
+/// assert_eq!(div_rem(&BigUint::from(17u32), &BigUint::from(5u32)), (BigUint::from(3u32), BigUint::from(2u32)));
+/// ```
+pub fn div_rem(a: &BigUint, b: &BigUint) -> (BigUint, BigUint) {
+    if b.is_zero() {
+        return (BigUint::zero(), BigUint::zero());
+    }
+    a.div_rem(b)
+}
+
+/// Computes the repunit `R_n` in a given base, the number written as `n` ones in that base.
+///
+/// For `base >= 2` this equals `(base^n - 1) / (base - 1)`. It is built digit by digit, so base 1 gives `n`.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing the number of digits.
+/// * `base` - A `u64` representing the base.
+///
+/// # Returns
+///
+/// The repunit `R_n` as a `BigUint`, which is 0 when `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::repunit;
+///
+/// assert_eq!(repunit(3, 10), BigUint::from(111u32));
+/// assert_eq!(repunit(4, 2), BigUint::from(15u32));
+/// ```
+pub fn repunit(n: u64, base: u64) -> BigUint {
+    let mut result = BigUint::zero();
+    for _ in 0..n {
+        result = result * base + BigUint::one();
+    }
+    result
+}
+
+/// Checks whether the repunit `R_n` in a given base is prime, using `miller_rabin`.
+///
+/// # Arguments
+///
+/// * `n` - A `u64` representing the number of digits.
+/// * `base` - A `u64` representing the base.
+///
+/// # Returns
+///
+/// * `true` if `repunit(n, base)` passes `miller_rabin`.
+/// * `false` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::utils::is_repunit_prime;
+///
+/// assert!(is_repunit_prime(19, 10));
+/// assert!(!is_repunit_prime(3, 10));
+/// ```
+pub fn is_repunit_prime(n: u64, base: u64) -> bool {
+    miller_rabin(&repunit(n, base))
+}
+
+/// Counts the set bits in the binary representation of a `BigUint`, also known as its Hamming weight.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` whose set bits are counted.
+///
+/// # Returns
+///
+/// The number of bits equal to 1 in `n`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::popcount;
+///
+/// assert_eq!(popcount(&BigUint::from(7u32)), 3);
+/// ```
+pub fn popcount(n: &BigUint) -> u64 {
+    n.count_ones()
+}
+
+/// Reverses the lowest `width` bits of a `BigUint`.
+///
+/// Bit `i` of `n` becomes bit `width - 1 - i` of the result. Bits of `n` at positions `width` and above
+/// are ignored, so the result always fits in `width` bits.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` whose bits are reversed.
+/// * `width` - A `u64` representing the number of low bits to reverse.
+///
+/// # Returns
+///
+/// A `BigUint` holding the reversed bits.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::bit_reverse;
+///
+/// // 0001 -> 1000
+/// assert_eq!(bit_reverse(&BigUint::from(1u32), 4), BigUint::from(8u32));
+/// ```
+pub fn bit_reverse(n: &BigUint, width: u64) -> BigUint {
+    let mut result = BigUint::zero();
+    for i in 0..width.min(n.bits()) {
+        if n.bit(i) {
+            result.set_bit(width - 1 - i, true);
+        }
+    }
+    result
+}
+
+/// Reconstructs an integer from its Zeckendorf representation, a sum of Fibonacci numbers.
+///
+/// Fibonacci numbers are indexed with `F_0 = 0`, `F_1 = 1`, `F_2 = 1`, `F_3 = 2`, `F_4 = 3`, and so on.
+/// Zeckendorf's theorem writes every positive integer uniquely as a sum of non-consecutive Fibonacci numbers
+/// with indices of at least 2, for example `4 = F_2 + F_4`. The indices are summed as given, so they do not
+/// have to be sorted, and a representation that is not a valid Zeckendorf one still yields its sum.
+///
+/// # Arguments
+///
+/// * `indices` - A slice of `u64` Fibonacci indices.
+///
+/// # Returns
+///
+/// The sum of `F_i` over the given indices, which is 0 for an empty slice.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::utils::from_zeckendorf;
+///
+/// // F_2 + F_4 = 1 + 3
+/// assert_eq!(from_zeckendorf(&[2, 4]), BigUint::from(4u32));
+/// // F_3 + F_5 + F_10 = 2 + 5 + 55
+/// assert_eq!(from_zeckendorf(&[3, 5, 10]), BigUint::from(62u32));
+/// ```
+pub fn from_zeckendorf(indices: &[u64]) -> BigUint {
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+
+    // Walk the Fibonacci sequence once, picking up F_i at each requested index
+    let mut result = BigUint::zero();
+    let mut index = 0u64;
+    let mut current = BigUint::zero();
+    let mut next = BigUint::one();
+    for i in sorted {
+        while index < i {
+            let following = &current + &next;
+            current = core::mem::replace(&mut next, following);
+            index += 1;
+        }
+        result += &current;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_check() {
+        // Test case 0, 1: False
+        assert_eq!(quick_composite_check(&BigUint::zero()), Some(false));
+        assert_eq!(quick_composite_check(&BigUint::one()), Some(false));
+
+        // Test case 2, 3, 5: True
+        assert_eq!(quick_composite_check(&BigUint::from(2u32)), Some(true));
+        assert_eq!(quick_composite_check(&BigUint::from(3u32)), Some(true));
+        assert_eq!(quick_composite_check(&BigUint::from(5u32)), Some(true));
+
+        // Test case 1000000: False
+        assert_eq!(quick_composite_check(&1000000u64.into()), Some(false));
+
+        // Test case 7, 49: Undecided
+        assert_eq!(quick_composite_check(&BigUint::from(7u32)), None);
+        assert_eq!(quick_composite_check(&BigUint::from(49u32)), None);
+    }
+
+    #[test]
+    fn random_bases() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let mut rng = StdRng::seed_from_u64(28);
+
+        // Test case 4: only 2 fits
+        for _ in 0..100 {
+            assert_eq!(random_base(&BigUint::from(4u32), &mut rng), BigUint::from(2u32));
+        }
+
+        // Test case 7: every value of [2, 5] shows up
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let base = random_base(&BigUint::from(7u32), &mut rng);
+            seen[base.to_u64_digits()[0] as usize] = true;
+        }
+        assert_eq!(seen, [false, false, true, true, true, true]);
+
+        // Test case large: always within [2, n - 2]
+        let n = BigUint::parse_bytes(b"7156857700403137441", 10).unwrap();
+        for _ in 0..10000 {
+            let base = random_base(&n, &mut rng);
+            assert!(base >= BigUint::from(2u32));
+            assert!(base <= &n - BigUint::from(2u32));
+        }
+    }
+
+    #[test]
+    fn factorials() {
+        // Test case 0! mod 7: 1
+        assert_eq!(modular_factorial(&BigUint::zero(), &BigUint::from(7u32)), BigUint::one());
+
+        // Test case 5! mod 1000: 120
+        assert_eq!(modular_factorial(&BigUint::from(5u32), &BigUint::from(1000u32)), BigUint::from(120u32));
+
+        // Test case 10! mod 7: 0
+        assert_eq!(modular_factorial(&BigUint::from(10u32), &BigUint::from(7u32)), BigUint::zero());
+
+        // Test case 6! mod 7: 6 (Wilson's theorem)
+        assert_eq!(modular_factorial(&BigUint::from(6u32), &BigUint::from(7u32)), BigUint::from(6u32));
+
+        // Test case anything mod 1: 0
+        assert_eq!(modular_factorial(&BigUint::zero(), &BigUint::one()), BigUint::zero());
+    }
+
+    #[test]
+    fn binomials() {
+        let binomial = |n: &[u8], k: &[u8], p: &[u8]| {
+            binomial_mod_prime(
+                &BigUint::parse_bytes(n, 10).unwrap(),
+                &BigUint::parse_bytes(k, 10).unwrap(),
+                &BigUint::parse_bytes(p, 10).unwrap()
+            )
+        };
+
+        // Test case C(10, 3) mod 7: 120 mod 7 = 1
+        assert_eq!(binomial(b"10", b"3", b"7"), BigUint::one());
+
+        // Test case k > n: 0
+        assert_eq!(binomial(b"3", b"10", b"7"), BigUint::zero());
+
+        // Test case C(n, 0): 1
+        assert_eq!(binomial(b"123456789", b"0", b"7"), BigUint::one());
+
+        // Test case 25-digit n, far beyond direct computation
+        assert_eq!(
+            binomial(b"1000008500021777784833302", b"123457511109733323933344", b"1000003"),
+            BigUint::from(488993u32)
+        );
+    }
+
+    #[test]
+    fn bits() {
+        assert_eq!(popcount(&BigUint::from(7u32)), 3);
+        assert_eq!(popcount(&BigUint::from(8u32)), 1);
+        assert_eq!(popcount(&BigUint::zero()), 0);
+
+        assert_eq!(bit_reverse(&BigUint::from(1u32), 4), BigUint::from(8u32));
+        assert_eq!(bit_reverse(&BigUint::from(0b1101u32), 4), BigUint::from(0b1011u32));
+        assert_eq!(bit_reverse(&BigUint::from(0b110u32), 2), BigUint::from(0b01u32));
+        assert_eq!(bit_reverse(&BigUint::from(1u32), 0), BigUint::zero());
+    }
+
+    #[test]
+    fn square_root() {
+        assert_eq!(isqrt(&BigUint::zero()), BigUint::zero());
+        assert_eq!(isqrt(&BigUint::one()), BigUint::one());
+        assert_eq!(isqrt(&BigUint::from(15u32)), BigUint::from(3u32));
+        assert_eq!(isqrt(&BigUint::from(16u32)), BigUint::from(4u32));
+
+        // A 100 digit perfect square and its neighbour below
+        let root = BigUint::parse_bytes(b"98765432109876543210987654321098765432109876543210", 10).unwrap();
+        let square = &root * &root;
+        assert_eq!(square.to_string().len(), 100);
+        assert_eq!(isqrt(&square), root);
+        assert_eq!(isqrt(&(&square - BigUint::one())), &root - BigUint::one());
+    }
+
+    #[test]
+    fn division() {
+        assert_eq!(div_rem(&BigUint::from(17u32), &BigUint::from(5u32)), (BigUint::from(3u32), BigUint::from(2u32)));
+        assert_eq!(div_rem(&BigUint::from(20u32), &BigUint::from(4u32)), (BigUint::from(5u32), BigUint::zero()));
+        assert_eq!(div_rem(&BigUint::from(3u32), &BigUint::from(5u32)), (BigUint::zero(), BigUint::from(3u32)));
+        assert_eq!(div_rem(&BigUint::from(3u32), &BigUint::zero()), (BigUint::zero(), BigUint::zero()));
+    }
+
+    #[test]
+    fn repunits() {
+        assert_eq!(repunit(3, 10), BigUint::from(111u32));
+        assert_eq!(repunit(0, 10), BigUint::zero());
+        assert_eq!(repunit(1, 10), BigUint::one());
+        assert_eq!(repunit(5, 1), BigUint::from(5u32));
+        for n in 1..20u32 {
+            // (b^n - 1) / (b - 1)
+            let expected = (BigUint::from(7u32).pow(n) - BigUint::one()) / BigUint::from(6u32);
+            assert_eq!(repunit(n as u64, 7), expected);
+        }
+
+        // Base 10 repunit primes below 30 digits occur at n = 2, 19 and 23
+        let primes: Vec<u64> = (1..30).filter(|&n| is_repunit_prime(n, 10)).collect();
+        assert_eq!(primes, vec![2, 19, 23]);
+
+        // Mersenne primes are the base 2 repunit primes
+        assert_eq!(is_repunit_prime(13, 2), true);
+        assert_eq!(is_repunit_prime(11, 2), false);
+    }
+
+    #[test]
+    fn zeckendorf() {
+        // F_2 = 1, F_4 = 3
+        assert_eq!(from_zeckendorf(&[2, 4]), BigUint::from(4u32));
+        assert_eq!(from_zeckendorf(&[]), BigUint::zero());
+        assert_eq!(from_zeckendorf(&[0]), BigUint::zero());
+        assert_eq!(from_zeckendorf(&[1]), BigUint::one());
+
+        // Order does not matter
+        assert_eq!(from_zeckendorf(&[10, 3, 5]), BigUint::from(62u32));
+
+        // The Zeckendorf representations of 1 to 20
+        let representations: [&[u64]; 20] = [
+            &[2], &[3], &[4], &[2, 4], &[5], &[2, 5], &[3, 5], &[6], &[2, 6], &[3, 6],
+            &[4, 6], &[2, 4, 6], &[7], &[2, 7], &[3, 7], &[4, 7], &[2, 4, 7], &[5, 7], &[2, 5, 7], &[3, 5, 7],
+        ];
+        for (n, indices) in representations.iter().enumerate() {
+            assert_eq!(from_zeckendorf(indices), BigUint::from(n as u32 + 1));
+        }
+
+        // Large indices, including the Fibonacci prime F_83
+        assert_eq!(from_zeckendorf(&[100]), BigUint::parse_bytes(b"354224848179261915075", 10).unwrap());
+        assert_eq!(miller_rabin(&from_zeckendorf(&[83])), true);
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+use num_bigint::BigUint;
+
+/// The error returned by `parse_flexible` when the input is not a valid number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input contained no digits.
+    Empty,
+    /// The input contained characters that are not valid digits in its base.
+    InvalidDigit(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no digits to parse"),
+            ParseError::InvalidDigit(input) => write!(f, "invalid number: {}", input),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a `BigUint` from user input, tolerating common formatting.
+///
+/// Surrounding whitespace is trimmed, and commas and underscores are dropped, so thousands separators
+/// such as `1,000,000` or `1_000_000` are accepted. A `0x` prefix selects hexadecimal and a `0b` prefix
+/// selects binary; anything else is read as decimal.
+///
+/// # Arguments
+///
+/// * `s` - The string to parse.
+///
+/// # Returns
+///
+/// * `Ok(number)` with the parsed number.
+/// * `Err(ParseError)` if no digits remain after cleanup, or if some character is not a valid digit.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::parse_flexible;
+///
+/// assert_eq!(parse_flexible("1,000,000,007"), Ok(BigUint::from(1000000007u32)));
+/// assert_eq!(parse_flexible(" 0xFF "), Ok(BigUint::from(255u32)));
+/// assert!(parse_flexible("12x3").is_err());
+/// ```
+pub fn parse_flexible(s: &str) -> Result<BigUint, ParseError> {
+    let cleaned: String = s
+        .trim()
+        .chars()
+        .filter(|c| *c != ',' && *c != '_')
+        .collect();
+
+    let lowered = cleaned.to_ascii_lowercase();
+    let (digits, radix) = if let Some(hex) = lowered.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(binary) = lowered.strip_prefix("0b") {
+        (binary, 2)
+    } else {
+        (lowered.as_str(), 10)
+    };
+
+    if digits.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(ParseError::InvalidDigit(s.to_string()));
+    }
+
+    BigUint::parse_bytes(digits.as_bytes(), radix).ok_or_else(|| ParseError::InvalidDigit(s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_inputs() {
+        assert_eq!(parse_flexible("1,000,000,007"), Ok(BigUint::from(1000000007u32)));
+        assert_eq!(parse_flexible("1_000_000"), Ok(BigUint::from(1000000u32)));
+        assert_eq!(parse_flexible(" 0xFF "), Ok(BigUint::from(255u32)));
+        assert_eq!(parse_flexible("0b1010"), Ok(BigUint::from(10u32)));
+        assert_eq!(parse_flexible("\t97\n"), Ok(BigUint::from(97u32)));
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        assert_eq!(parse_flexible("12x3"), Err(ParseError::InvalidDigit("12x3".to_string())));
+        assert_eq!(parse_flexible("0b102"), Err(ParseError::InvalidDigit("0b102".to_string())));
+        assert_eq!(parse_flexible("-5"), Err(ParseError::InvalidDigit("-5".to_string())));
+        assert_eq!(parse_flexible("   "), Err(ParseError::Empty));
+        assert_eq!(parse_flexible("0x"), Err(ParseError::Empty));
+    }
+}
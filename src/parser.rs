@@ -1,16 +1,56 @@
 use clap::Parser;
 use num_bigint::BigUint;
+use large_primes::parse_flexible;
 
 #[derive(clap::ValueEnum, Clone, Debug, PartialEq, Eq)]
 pub enum Action {
     Standard,
     Fermat,
     MillerRabin,
+    BailliePsw,
     Generate,
     Power,
     LucasLehmer,
+    Gap,
+    SumPrimes,
+    #[value(alias = "factor-count")]
+    Divisors,
+    NthPrime,
+    Repunit,
+    SolovayStrassen,
+    Factorize,
+    StripFactors,
+    RandomPrime,
 }
 
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq, Eq)]
+pub enum TimeUnit {
+    Ns,
+    Us,
+    Ms,
+    S,
+}
+
+/// An argument that an action needs but was not given, or that was given but cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// The named argument is required by the action.
+    Missing(&'static str),
+    /// The argument was given, but its value is unusable for the reason in the message.
+    Invalid(String),
+}
+
+impl std::fmt::Display for ArgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ArgError::Missing(argument) => write!(f, "Use <exe> --help for more information ({} is required)", argument),
+            ArgError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
@@ -19,20 +59,44 @@ pub struct Args {
     pub action: Action,
 
     /// The target number
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_flexible)]
     pub target: Option<BigUint>,
 
+    /// An environment variable holding the target number, for inputs too long for the command line
+    #[arg(long, conflicts_with = "target")]
+    pub target_env: Option<String>,
+
+    /// Read newline separated targets from stdin and test each one (Only used with primality tests)
+    #[arg(long, conflicts_with_all = ["target", "target_env"])]
+    pub stdin: bool,
+
     /// The power to be raised to (Only used when analysis is `power`)
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_flexible)]
     pub power: Option<BigUint>,
 
-    /// Number upto which primes to be generated (Only used when analysis is `generate`)
+    /// Number upto which primes to be generated (Only used when analysis is `generate` or `sum-primes`)
     #[arg(short, long)]
     pub maximum: Option<u64>,
 
     /// The exponent of mercenne prime for lucas lehmer test (Only used when analysis is `lucas-lehmer`)
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = parse_flexible)]
     pub mersenne_exp: Option<BigUint>,
+
+    /// The base of the repunit (Only used when analysis is `repunit`)
+    #[arg(short, long, default_value_t = 10)]
+    pub base: u64,
+
+    /// The bit length of the prime (Only used when analysis is `random-prime`)
+    #[arg(long)]
+    pub bits: Option<u64>,
+
+    /// The bound below which small factors are stripped (Only used when analysis is `strip-factors`)
+    #[arg(short, long, default_value_t = 1000)]
+    pub limit: u64,
+
+    /// Print the total time as a plain number in this unit instead of a human readable duration
+    #[arg(long)]
+    pub time_unit: Option<TimeUnit>,
 }
 
 impl Args {
@@ -40,46 +104,113 @@ impl Args {
         self.action.clone()
     }
 
-    pub fn get_target(&self) -> BigUint {
-        let target = self.target.clone();
-        match target {
-            Some(target) => target,
-            None => {
-                println!("Use <exe> --help for more information (--target is required)");
-                std::process::exit(1);
-            }
-        }
+    pub fn get_stdin(&self) -> bool {
+        self.stdin
     }
 
-    pub fn get_power(&self) -> BigUint {
-        let power = self.power.clone();
-        match power {
-            Some(power) => power,
-            None => {
-                println!("Use <exe> --help for more information (--power is required)");
-                std::process::exit(1);
-            }
-        }
+    pub fn get_limit(&self) -> u64 {
+        self.limit
     }
 
-    pub fn get_maximum(&self) -> u64 {
-        match self.maximum {
-            Some(maximum) => maximum,
-            None => {
-                println!("Use <exe> --help for more information (--maximum is required)");
-                std::process::exit(1);
-            }
-        }
+    pub fn get_base(&self) -> u64 {
+        self.base
+    }
+
+    pub fn get_time_unit(&self) -> Option<TimeUnit> {
+        self.time_unit.clone()
     }
 
-    pub fn get_mercenne_exp(&self) -> BigUint {
-        let mercenne_power = self.mersenne_exp.clone();
-        match mercenne_power {
-            Some(mercenne_power) => mercenne_power,
-            None => {
-                println!("Use <exe> --help for more information (--mercenne_power is required)");
-                std::process::exit(1);
+    pub fn get_target(&self) -> Result<BigUint, ArgError> {
+        self.resolve_target()?.ok_or(ArgError::Missing("--target or --target-env"))
+    }
+
+    /// Returns `--target`, or the number read from the variable named by `--target-env`.
+    pub fn resolve_target(&self) -> Result<Option<BigUint>, ArgError> {
+        if let Some(target) = &self.target {
+            return Ok(Some(target.clone()));
+        }
+        match &self.target_env {
+            Some(name) => {
+                let value = std::env::var(name)
+                    .map_err(|_| ArgError::Invalid(format!("Environment variable {} is not set", name)))?;
+                parse_flexible(&value)
+                    .map(Some)
+                    .map_err(|error| ArgError::Invalid(format!("Invalid number in environment variable {}: {}", name, error)))
             }
+            None => Ok(None),
         }
     }
+
+    pub fn get_power(&self) -> Result<BigUint, ArgError> {
+        self.power.clone().ok_or(ArgError::Missing("--power"))
+    }
+
+    pub fn get_maximum(&self) -> Result<u64, ArgError> {
+        self.maximum.ok_or(ArgError::Missing("--maximum"))
+    }
+
+    pub fn get_bits(&self) -> Result<u64, ArgError> {
+        self.bits.ok_or(ArgError::Missing("--bits"))
+    }
+
+    pub fn get_mercenne_exp(&self) -> Result<BigUint, ArgError> {
+        self.mersenne_exp.clone().ok_or(ArgError::Missing("--mersenne-exp"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_from_env() {
+        std::env::set_var("PRIMES_TEST_TARGET", "1_000_000_007");
+        let args = Args::try_parse_from(["primes", "-a", "standard", "--target-env", "PRIMES_TEST_TARGET"]).unwrap();
+        assert_eq!(args.resolve_target(), Ok(Some(BigUint::from(1000000007u32))));
+
+        std::env::set_var("PRIMES_TEST_BAD_TARGET", "12x");
+        let args = Args::try_parse_from(["primes", "-a", "standard", "--target-env", "PRIMES_TEST_BAD_TARGET"]).unwrap();
+        assert!(args.resolve_target().is_err());
+
+        let args = Args::try_parse_from(["primes", "-a", "standard", "--target-env", "PRIMES_TEST_UNSET"]).unwrap();
+        assert!(args.resolve_target().is_err());
+
+        let args = Args::try_parse_from(["primes", "-a", "standard", "-t", "97"]).unwrap();
+        assert_eq!(args.resolve_target(), Ok(Some(BigUint::from(97u32))));
+
+        let args = Args::try_parse_from(["primes", "-a", "generate"]).unwrap();
+        assert_eq!(args.resolve_target(), Ok(None));
+
+        assert!(Args::try_parse_from(["primes", "-a", "standard", "-t", "97", "--target-env", "PRIMES_TEST_TARGET"]).is_err());
+    }
+
+    #[test]
+    fn stdin_flag() {
+        let args = Args::try_parse_from(["primes", "-a", "miller-rabin", "--stdin"]).unwrap();
+        assert!(args.get_stdin());
+
+        let args = Args::try_parse_from(["primes", "-a", "miller-rabin", "-t", "97"]).unwrap();
+        assert!(!args.get_stdin());
+
+        assert!(Args::try_parse_from(["primes", "-a", "miller-rabin", "-t", "97", "--stdin"]).is_err());
+    }
+
+    #[test]
+    fn missing_arguments() {
+        let args = Args::try_parse_from(["primes", "-a", "power"]).unwrap();
+        assert_eq!(args.get_target(), Err(ArgError::Missing("--target or --target-env")));
+        assert_eq!(args.get_power(), Err(ArgError::Missing("--power")));
+        assert_eq!(args.get_maximum(), Err(ArgError::Missing("--maximum")));
+        assert_eq!(args.get_bits(), Err(ArgError::Missing("--bits")));
+        assert_eq!(args.get_mercenne_exp(), Err(ArgError::Missing("--mersenne-exp")));
+        assert_eq!(
+            ArgError::Missing("--power").to_string(),
+            "Use <exe> --help for more information (--power is required)"
+        );
+
+        let args = Args::try_parse_from(["primes", "-a", "power", "-t", "2", "-p", "0x10", "-m", "100"]).unwrap();
+        assert_eq!(args.get_target(), Ok(BigUint::from(2u32)));
+        assert_eq!(args.get_power(), Ok(BigUint::from(16u32)));
+        assert_eq!(args.get_maximum(), Ok(100));
+    }
 }
@@ -0,0 +1,192 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use num_bigint::BigUint;
+use num_traits::{ One, ToPrimitive, Zero };
+use crate::operations::{ euler_totient, gcd, is_perfect_power };
+use crate::operations::utils::isqrt;
+
+/// Performs the AKS primality test, a deterministic polynomial-time test.
+///
+/// The Agrawal-Kayal-Saxena algorithm proves primality without relying on any unproven conjecture:
+///
+/// 1. Perfect powers are composite, checked with `is_perfect_power`.
+/// 2. Find the smallest `r` such that the multiplicative order of `num` modulo `r` exceeds `log2(num)^2`.
+/// 3. If some `a <= r` has `1 < gcd(a, num) < num`, then `num` is composite.
+/// 4. If `num <= r`, then `num` is prime.
+/// 5. For every `a` from 1 to `sqrt(φ(r)) * log2(num)`, check that `(x + a)^num ≡ x^num + a` modulo
+///    `x^r - 1` and `num`. `num` is prime exactly when all of these hold.
+///
+/// The bit length of `num` is used in place of `log2(num)`. It is never smaller, so the bounds only grow and
+/// the result stays correct.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` is prime.
+/// * `false` if `num` is composite or less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::aks;
+///
+/// assert!(aks(&BigUint::from(1009u32)));
+///
+/// // 561 = 3 * 11 * 17 is a Carmichael number
+/// assert!(!aks(&BigUint::from(561u32)));
+/// ```
+///
+/// # Note
+///
+/// The test is meant for teaching, not for use on large numbers. For a `b`-bit number, `r` grows roughly
+/// like `b^2`, and step 5 raises a polynomial with `r` coefficients to the power `num` for each of about
+/// `b^2` values of `a`. That is about `b^3` polynomial multiplications of `b^4` coefficient products each.
+/// Numbers of 10 bits take well under a second, while 20 bits already take several seconds and every
+/// further bit makes it slower. `miller_rabin` and `baillie_psw` answer instantly far beyond that.
+pub fn aks(num: &BigUint) -> bool {
+    if *num <= BigUint::one() {
+        return false;
+    }
+
+    // Step 1: perfect powers are composite
+    if is_perfect_power(num).is_some() {
+        return false;
+    }
+
+    // Step 2: the smallest r with ord_r(num) > log2(num)^2
+    let log = num.bits();
+    let r = smallest_order_modulus(num, log * log);
+
+    // Step 3: a factor up to r proves num composite
+    for a in 2..=r {
+        let a = BigUint::from(a);
+        if a >= *num {
+            break;
+        }
+        if !gcd(&a, num).is_one() {
+            return false;
+        }
+    }
+
+    // Step 4: no factor up to r, and num itself is at most r
+    if *num <= BigUint::from(r) {
+        return true;
+    }
+
+    // Step 5: (x + a)^num = x^(num mod r) + a in Z_num[x] / (x^r - 1)
+    let limit = isqrt(&(euler_totient(&BigUint::from(r)) * log * log)).to_u64().unwrap();
+    (1..=limit).all(|a| polynomial_congruence(num, r, a))
+}
+
+/// Finds the smallest `r` coprime to `num` such that `num^k mod r` is not 1 for any `k` from 1 to `max_k`.
+fn smallest_order_modulus(num: &BigUint, max_k: u64) -> u64 {
+    let mut r = 2u64;
+    loop {
+        if gcd(num, &BigUint::from(r)).is_one() {
+            let residue = (num % r).to_u64().unwrap();
+            let mut power = 1u64;
+            let order_exceeds = (1..=max_k).all(|_| {
+                power = power * residue % r;
+                power != 1
+            });
+            if order_exceeds {
+                return r;
+            }
+        }
+        r += 1;
+    }
+}
+
+/// Checks whether `(x + a)^num ≡ x^(num mod r) + a` modulo `x^r - 1` and `num`.
+fn polynomial_congruence(num: &BigUint, r: u64, a: u64) -> bool {
+    let r = r as usize;
+    let a = BigUint::from(a) % num;
+
+    // x + a, raised to the power num by square and multiply
+    let mut base = vec![BigUint::zero(); r];
+    base[0] = a.clone();
+    base[1] = BigUint::one();
+    let mut result = vec![BigUint::zero(); r];
+    result[0] = BigUint::one();
+    for i in (0..num.bits()).rev() {
+        result = polynomial_mul_mod(&result, &result, num);
+        if num.bit(i) {
+            result = polynomial_mul_mod(&result, &base, num);
+        }
+    }
+
+    // x^(num mod r) + a
+    let mut expected = vec![BigUint::zero(); r];
+    expected[(num % r).to_usize().unwrap()] = BigUint::one();
+    expected[0] = (&expected[0] + &a) % num;
+
+    result == expected
+}
+
+/// Multiplies two polynomials with `r` coefficients each, modulo `x^r - 1` and `modulus`.
+fn polynomial_mul_mod(p: &[BigUint], q: &[BigUint], modulus: &BigUint) -> Vec<BigUint> {
+    let r = p.len();
+    let mut product = vec![BigUint::zero(); r];
+    for (i, pi) in p.iter().enumerate() {
+        if pi.is_zero() {
+            continue;
+        }
+        for (j, qj) in q.iter().enumerate() {
+            if !qj.is_zero() {
+                product[(i + j) % r] += pi * qj;
+            }
+        }
+    }
+    for coefficient in product.iter_mut() {
+        *coefficient %= modulus;
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primality::standard;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0, 1: False
+        assert_eq!(aks(&BigUint::zero()), false);
+        assert_eq!(aks(&BigUint::one()), false);
+
+        // Test case 2, 3: True
+        assert_eq!(aks(&BigUint::from(2u32)), true);
+        assert_eq!(aks(&BigUint::from(3u32)), true);
+
+        // Test case perfect powers: False
+        for n in [4u32, 8, 27, 1024, 2187, 3125] {
+            assert_eq!(aks(&BigUint::from(n)), false);
+        }
+    }
+
+    #[test]
+    fn agrees_with_standard() {
+        for n in 0..400u32 {
+            let n = BigUint::from(n);
+            assert_eq!(aks(&n), standard(&n));
+        }
+    }
+
+    #[test]
+    fn past_step_four() {
+        // Numbers above r, so the verdict comes from the polynomial congruences
+        for p in [1009u32, 1013, 2003, 4099] {
+            assert_eq!(aks(&BigUint::from(p)), true);
+        }
+
+        // Carmichael numbers have small factors caught by the gcd checks, while products of two primes
+        // above r only fail the congruences
+        for n in [561u32, 1105, 1729, 2465, 1009 * 1013, 4093 * 4099] {
+            assert_eq!(aks(&BigUint::from(n)), false);
+        }
+    }
+}
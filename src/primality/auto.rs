@@ -0,0 +1,565 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{ Hash, Hasher };
+use std::sync::mpsc;
+use std::thread;
+use std::time::{ Duration, Instant };
+use num_bigint::BigUint;
+use super::baillie_psw::baillie_psw;
+use super::miller_rabin::{ miller_rabin, miller_rabin_with_witnesses };
+use super::standard::standard;
+use crate::generators::get_max_primes_u64;
+
+/// Numbers above this many bits are never raced against `standard`, since trial division could not finish.
+const RACE_STANDARD_BITS: u64 = 64;
+
+/// The level of confidence requested from `is_likely_prime`, trading speed for accuracy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Accuracy {
+    /// Two Miller-Rabin rounds with bases 2 and 3. Cheap, but fooled by some composites.
+    Fast,
+    /// The fixed witness set of `miller_rabin`.
+    Balanced,
+    /// Baillie-PSW followed by `miller_rabin`. No composite is known to pass it.
+    Paranoid,
+}
+
+/// The size boundaries used by `is_prime_with_policy` to pick a primality test.
+///
+/// Numbers up to `trial_limit` are tested exactly with `standard`. Numbers of at most `deterministic_bits`
+/// bits go through `miller_rabin`, and everything larger through `baillie_psw`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrimalityPolicy {
+    /// The largest number tested by trial division.
+    pub trial_limit: u64,
+    /// The largest bit length tested with the fixed witnesses of `miller_rabin`.
+    pub deterministic_bits: u64,
+}
+
+impl Default for PrimalityPolicy {
+    /// Trial division up to 10^6, and `miller_rabin` up to 64 bits, where its witnesses are deterministic.
+    fn default() -> Self {
+        PrimalityPolicy {
+            trial_limit: 1_000_000,
+            deterministic_bits: 64,
+        }
+    }
+}
+
+/// The test chosen by a `PrimalityPolicy` for a given number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    Standard,
+    MillerRabin,
+    BailliePsw,
+}
+
+/// Picks the test `policy` uses for `num`.
+fn route(num: &BigUint, policy: &PrimalityPolicy) -> Algorithm {
+    if *num <= BigUint::from(policy.trial_limit) {
+        Algorithm::Standard
+    } else if num.bits() <= policy.deterministic_bits {
+        Algorithm::MillerRabin
+    } else {
+        Algorithm::BailliePsw
+    }
+}
+
+/// Tests whether a number is prime, choosing the test from the size of the number.
+///
+/// This is `is_prime_with_policy` with `PrimalityPolicy::default()`.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` is prime.
+/// * `false` if `num` is composite, or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_prime;
+///
+/// assert!(is_prime(&BigUint::from(1000000007u32)));
+/// assert!(!is_prime(&BigUint::parse_bytes(b"2152302898747", 10).unwrap()));
+/// ```
+pub fn is_prime(num: &BigUint) -> bool {
+    is_prime_with_policy(num, &PrimalityPolicy::default())
+}
+
+/// Tests whether a number is prime, choosing the test from the size boundaries of a policy.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `policy` - A reference to the `PrimalityPolicy` holding the crossover points.
+///
+/// # Returns
+///
+/// * `true` if `num` passes the test selected by `policy`.
+/// * `false` if `num` fails it, or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::{ is_prime_with_policy, PrimalityPolicy };
+///
+/// let policy = PrimalityPolicy { trial_limit: 1000, deterministic_bits: 32 };
+/// assert!(is_prime_with_policy(&BigUint::from(65537u32), &policy));
+/// ```
+pub fn is_prime_with_policy(num: &BigUint, policy: &PrimalityPolicy) -> bool {
+    match route(num, policy) {
+        Algorithm::Standard => standard(num),
+        Algorithm::MillerRabin => miller_rabin(num),
+        Algorithm::BailliePsw => is_prime_recommended(num),
+    }
+}
+
+/// Tests whether a number is prime with the recommended general-purpose test, Baillie-PSW.
+///
+/// Baillie-PSW is a strong Fermat test to base 2 followed by a strong Lucas test with Selfridge's
+/// parameters. No composite number is known to pass it, it has been verified to have no counter example
+/// below 2^64, and it costs only a few modular exponentiations, so it is the test to reach for when
+/// nothing else is known about the input. `is_prime` uses it for numbers above its deterministic range.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` passes the Baillie-PSW test.
+/// * `false` if `num` fails it, or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_prime_recommended;
+///
+/// let mersenne = (BigUint::from(1u32) << 127u32) - BigUint::from(1u32);
+/// assert!(is_prime_recommended(&mersenne));
+/// assert!(!is_prime_recommended(&(mersenne + BigUint::from(2u32))));
+/// ```
+pub fn is_prime_recommended(num: &BigUint) -> bool {
+    baillie_psw(num)
+}
+
+/// Tests whether a number is likely prime, with a simple dial between speed and accuracy.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `level` - The `Accuracy` deciding which tests are run.
+///
+/// # Returns
+///
+/// * `true` if `num` passes every test selected by `level`.
+/// * `false` if `num` fails any of them, or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::{ is_likely_prime, Accuracy };
+///
+/// let number = BigUint::from(97u32);
+/// assert!(is_likely_prime(&number, Accuracy::Fast));
+///
+/// let pseudoprime = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+/// assert!(!is_likely_prime(&pseudoprime, Accuracy::Paranoid));
+/// ```
+pub fn is_likely_prime(num: &BigUint, level: Accuracy) -> bool {
+    match level {
+        Accuracy::Fast => miller_rabin_with_witnesses(num, &[2, 3]),
+        Accuracy::Balanced => miller_rabin(num),
+        Accuracy::Paranoid => baillie_psw(num) && miller_rabin(num),
+    }
+}
+
+/// Tests primality by racing `miller_rabin` against `standard` on separate threads.
+///
+/// `standard` is only started for numbers of at most 64 bits. Its verdict is exact and is returned as soon
+/// as it arrives. A `false` from `miller_rabin` is also exact, since a failed witness proves compositeness.
+/// A `true` from `miller_rabin` is kept as a fallback and returned once the timeout expires without an
+/// answer from `standard`, or immediately when `standard` was not started.
+///
+/// Threads cannot be cancelled, so a `standard` thread that loses the race keeps running in the background
+/// until it finishes.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `timeout` - The `Duration` to wait for an answer.
+///
+/// # Returns
+///
+/// * `Some(verdict)` with the exact verdict, or the probabilistic one if the exact test did not finish in time.
+/// * `None` if no test finished before the timeout.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use num_bigint::BigUint;
+/// use large_primes::race_primality;
+///
+/// assert_eq!(race_primality(&BigUint::from(97u32), Duration::from_secs(5)), Some(true));
+/// assert_eq!(race_primality(&BigUint::from(91u32), Duration::from_secs(5)), Some(false));
+/// ```
+pub fn race_primality(num: &BigUint, timeout: Duration) -> Option<bool> {
+    race_against(num, timeout, standard)
+}
+
+/// Runs the race of `race_primality` with `exact` in place of `standard`.
+fn race_against(num: &BigUint, timeout: Duration, exact: fn(&BigUint) -> bool) -> Option<bool> {
+    let deadline = Instant::now() + timeout;
+    let (sender, receiver) = mpsc::channel();
+
+    let probable_num = num.clone();
+    let probable_sender = sender.clone();
+    thread::spawn(move || {
+        let _ = probable_sender.send((false, miller_rabin(&probable_num)));
+    });
+
+    if num.bits() <= RACE_STANDARD_BITS {
+        let exact_num = num.clone();
+        thread::spawn(move || {
+            let _ = sender.send((true, exact(&exact_num)));
+        });
+    } else {
+        drop(sender);
+    }
+
+    let mut probable = None;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(remaining) {
+            Ok((true, verdict)) => {
+                return Some(verdict);
+            }
+            Ok((false, false)) => {
+                return Some(false);
+            }
+            Ok((false, true)) => {
+                probable = Some(true);
+            }
+            Err(_) => {
+                return probable;
+            }
+        }
+    }
+}
+
+/// Lists every integer up to a bound together with its primality, as ground truth for regression tests.
+///
+/// The verdicts come from the sieve of Eratosthenes rather than from any primality test, so they can be
+/// used to check other primality code, including the tests in this crate.
+///
+/// # Arguments
+///
+/// * `upper` - A `u64` representing the largest integer in the corpus.
+///
+/// # Returns
+///
+/// A vector of `(n, is_prime)` pairs for every `n` from 0 to `upper` inclusive, in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::{ export_test_corpus, miller_rabin };
+///
+/// for (n, is_prime) in export_test_corpus(1000) {
+///     assert_eq!(miller_rabin(&n), is_prime);
+/// }
+/// ```
+pub fn export_test_corpus(upper: u64) -> Vec<(BigUint, bool)> {
+    let mut primes = get_max_primes_u64(upper).into_iter().peekable();
+    (0..=upper)
+        .map(|n| {
+            let is_prime = primes.next_if_eq(&n).is_some();
+            (BigUint::from(n), is_prime)
+        })
+        .collect()
+}
+
+/// A bloom filter of numbers known to be composite, used to skip repeated primality tests.
+///
+/// A bloom filter can report a number it never saw as present, but never misses a number that was
+/// inserted. Since only composites are inserted, `is_prime` never calls a composite prime because of the
+/// cache. The price is that a prime colliding with the recorded composites is reported composite, which
+/// happens with the false positive rate of the filter, so size it generously for the expected number of
+/// composites.
+#[derive(Clone, Debug)]
+pub struct CompositeBloom {
+    bits: Vec<u64>,
+    num_bits: u64,
+    hashes: u32,
+    tests_run: u64,
+}
+
+impl CompositeBloom {
+    /// Creates an empty filter with `num_bits` bits and `hashes` hash functions per number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` or `hashes` is 0.
+    pub fn new(num_bits: u64, hashes: u32) -> Self {
+        assert!(num_bits > 0 && hashes > 0, "CompositeBloom needs at least one bit and one hash");
+        CompositeBloom {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            hashes,
+            tests_run: 0,
+        }
+    }
+
+    /// Records `n` as a known composite.
+    pub fn insert_composite(&mut self, n: &BigUint) {
+        for bit in self.bit_positions(n) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `true` if `n` has certainly never been recorded as composite, and `false` if it probably has.
+    pub fn probably_untested(&self, n: &BigUint) -> bool {
+        self.bit_positions(n).any(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) == 0)
+    }
+
+    /// Tests whether `n` is prime like `is_prime`, skipping the test for recorded composites and recording
+    /// any new composite found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint::BigUint;
+    /// use large_primes::CompositeBloom;
+    ///
+    /// let mut cache = CompositeBloom::new(1 << 16, 4);
+    /// let n = BigUint::from(1000000011u32);
+    /// assert!(!cache.is_prime(&n));
+    /// assert!(!cache.probably_untested(&n));
+    /// assert!(cache.is_prime(&BigUint::from(1000000007u32)));
+    /// ```
+    pub fn is_prime(&mut self, n: &BigUint) -> bool {
+        if !self.probably_untested(n) {
+            return false;
+        }
+        self.tests_run += 1;
+        let verdict = is_prime(n);
+        if !verdict {
+            self.insert_composite(n);
+        }
+        verdict
+    }
+
+    /// Derives the bit positions of `n` by double hashing two keyed hashes of it.
+    fn bit_positions(&self, n: &BigUint) -> impl Iterator<Item = u64> {
+        let hash = |key: u8| {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            n.hash(&mut hasher);
+            hasher.finish()
+        };
+        let (first, second) = (hash(0), hash(1));
+        let num_bits = self.num_bits;
+        (0..self.hashes as u64).map(move |i| first.wrapping_add(i.wrapping_mul(second)) % num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEVELS: [Accuracy; 3] = [Accuracy::Fast, Accuracy::Balanced, Accuracy::Paranoid];
+
+    #[test]
+    fn levels_agree() {
+        let primes = [
+            // 10-12 digit primes
+            "871603259",
+            "98762051",
+            "1000000007",
+            "123575321",
+            "193818613",
+            "444444443",
+            "999999937",
+            "1000000000039",
+            "9999999929",
+        ];
+
+        for prime in primes {
+            let prime = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+            for level in LEVELS {
+                assert_eq!(is_likely_prime(&prime, level), true);
+            }
+        }
+
+        for i in 0..primes.len() {
+            for j in 0..primes.len() {
+                if i == j {
+                    continue;
+                }
+                let composite =
+                    BigUint::parse_bytes(primes[i].as_bytes(), 10).unwrap() *
+                    BigUint::parse_bytes(primes[j].as_bytes(), 10).unwrap();
+                for level in LEVELS {
+                    assert_eq!(is_likely_prime(&composite, level), false);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn paranoid_counter_example() {
+        let counter_example = BigUint::parse_bytes(b"2152302898747", 10).unwrap();
+        assert_eq!(is_likely_prime(&counter_example, Accuracy::Paranoid), false);
+
+        let counter_example = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+        assert_eq!(is_likely_prime(&counter_example, Accuracy::Balanced), true);
+        assert_eq!(is_likely_prime(&counter_example, Accuracy::Paranoid), false);
+    }
+
+    #[test]
+    fn race() {
+        // Small numbers are settled exactly by `standard`
+        assert_eq!(race_primality(&BigUint::from(1000000007u32), Duration::from_secs(10)), Some(true));
+        assert_eq!(race_primality(&BigUint::from(1000000011u32), Duration::from_secs(10)), Some(false));
+
+        // An exact test that never answers leaves the probabilistic verdict once the timeout expires
+        fn never_answers(_: &BigUint) -> bool {
+            loop {
+                thread::park();
+            }
+        }
+        let large_prime = BigUint::parse_bytes(b"1125899906842597", 10).unwrap();
+        assert_eq!(race_against(&large_prime, Duration::from_secs(1), never_answers), Some(true));
+
+        // Too large to race at all, so the probabilistic verdict is returned as soon as it arrives
+        let mersenne = (BigUint::from(1u32) << 127u32) - BigUint::from(1u32);
+        assert_eq!(race_primality(&mersenne, Duration::from_secs(10)), Some(true));
+    }
+
+    #[test]
+    fn policies_agree() {
+        let policies = [
+            PrimalityPolicy { trial_limit: u64::MAX, deterministic_bits: 0 },
+            PrimalityPolicy { trial_limit: 0, deterministic_bits: 64 },
+            PrimalityPolicy { trial_limit: 0, deterministic_bits: 0 },
+        ];
+        let expected = [Algorithm::Standard, Algorithm::MillerRabin, Algorithm::BailliePsw];
+
+        for (number, verdict) in [(1000000007u64, true), (1000000011, false), (999999937 * 97, false)] {
+            let number = BigUint::from(number);
+            for (policy, algorithm) in policies.iter().zip(expected) {
+                assert_eq!(route(&number, policy), algorithm);
+                assert_eq!(is_prime_with_policy(&number, policy), verdict);
+            }
+            assert_eq!(is_prime(&number), verdict);
+        }
+
+        // The default policy keeps `miller_rabin` below its deterministic bound
+        let counter_example = BigUint::parse_bytes(b"2152302898747", 10).unwrap();
+        assert_eq!(route(&counter_example, &PrimalityPolicy::default()), Algorithm::MillerRabin);
+        assert_eq!(is_prime(&counter_example), false);
+
+        let counter_example = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+        assert_eq!(route(&counter_example, &PrimalityPolicy::default()), Algorithm::BailliePsw);
+        assert_eq!(is_prime(&counter_example), false);
+    }
+
+    #[test]
+    fn test_corpus() {
+        let expected: Vec<(BigUint, bool)> = [
+            (0u32, false),
+            (1, false),
+            (2, true),
+            (3, true),
+            (4, false),
+            (5, true),
+            (6, false),
+            (7, true),
+            (8, false),
+            (9, false),
+            (10, false),
+        ]
+            .iter()
+            .map(|&(n, is_prime)| (BigUint::from(n), is_prime))
+            .collect();
+        assert_eq!(export_test_corpus(10), expected);
+        assert_eq!(export_test_corpus(0), vec![(BigUint::from(0u32), false)]);
+
+        for (n, is_prime) in export_test_corpus(2000) {
+            assert_eq!(is_prime_with_policy(&n, &PrimalityPolicy { trial_limit: 0, deterministic_bits: 0 }), is_prime);
+        }
+    }
+
+    #[test]
+    fn composite_bloom() {
+        let mut cache = CompositeBloom::new(1 << 12, 3);
+        let composite = BigUint::from(999999937u64 * 97);
+        assert_eq!(cache.probably_untested(&composite), true);
+
+        assert_eq!(cache.is_prime(&composite), false);
+        assert_eq!(cache.tests_run, 1);
+        assert_eq!(cache.probably_untested(&composite), false);
+
+        // The second lookup is answered by the filter
+        assert_eq!(cache.is_prime(&composite), false);
+        assert_eq!(cache.tests_run, 1);
+
+        // Primes are never recorded, so they are tested every time
+        let prime = BigUint::from(1000000007u32);
+        assert_eq!(cache.is_prime(&prime), true);
+        assert_eq!(cache.is_prime(&prime), true);
+        assert_eq!(cache.tests_run, 3);
+
+        // A crowded filter may misreport primes as composite, but never composites as prime
+        let mut crowded = CompositeBloom::new(64, 1);
+        for (n, is_prime) in export_test_corpus(2000) {
+            if !is_prime {
+                crowded.insert_composite(&n);
+            }
+        }
+        for (n, is_prime) in export_test_corpus(2000) {
+            if !is_prime {
+                assert_eq!(crowded.is_prime(&n), false);
+            }
+        }
+        assert_eq!(crowded.tests_run, 0);
+    }
+
+    #[test]
+    fn recommended() {
+        for (n, is_prime) in export_test_corpus(100000) {
+            assert_eq!(is_prime_recommended(&n), is_prime);
+        }
+
+        let carmichaels = [
+            "561",
+            "41041",
+            "825265",
+            "321197185",
+            "5394826801",
+            "232250619601",
+            "9746347772161",
+            "1436697831295441",
+            "60977817398996785",
+            "7156857700403137441",
+        ];
+        for carmichael in carmichaels {
+            let carmichael = BigUint::parse_bytes(carmichael.as_bytes(), 10).unwrap();
+            assert_eq!(is_prime_recommended(&carmichael), false);
+        }
+
+        let counter_example = BigUint::parse_bytes(b"2152302898747", 10).unwrap();
+        assert_eq!(is_prime_recommended(&counter_example), false);
+    }
+}
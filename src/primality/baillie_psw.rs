@@ -0,0 +1,178 @@
+use num_bigint::BigUint;
+use num_traits::{ One, ToPrimitive, Zero };
+use crate::operations::utils::get_trailing_zeros;
+use super::miller_rabin::strong_probable_prime;
+use super::lucas::{ lucas_sequence, selfridge_parameter, sub_mod };
+
+/// Performs the Baillie-PSW primality test.
+///
+/// The Baillie-PSW test combines a strong probable prime test to base 2 with a strong Lucas probable
+/// prime test using Selfridge's parameters (`D` is the first of 5, -7, 9, -11, ... with Jacobi symbol
+/// `(D/num) = -1`, `P = 1`, `Q = (1 - D) / 4`). The two tests fail on very different kinds of
+/// composites, and no composite number is known to pass both, which makes the result reliable far
+/// above the range where the fixed witnesses of `miller_rabin` are deterministic.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` passes both the base 2 strong probable prime test and the strong Lucas test.
+/// * `false` if `num` fails either test, or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::baillie_psw;
+///
+/// assert!(baillie_psw(&BigUint::from(1000000007u32)));
+///
+/// // Fools the fixed witnesses of `miller_rabin`, but not Baillie-PSW
+/// let pseudoprime = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+/// assert!(!baillie_psw(&pseudoprime));
+/// ```
+pub fn baillie_psw(num: &BigUint) -> bool {
+    if *num <= BigUint::one() {
+        return false;
+    }
+
+    // Trial division by small primes also keeps `num` away from the Selfridge `D` values
+    for p in [2u32, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        let p = BigUint::from(p);
+        if *num == p {
+            return true;
+        }
+        if num % &p == BigUint::zero() {
+            return false;
+        }
+    }
+
+    if !strong_probable_prime(num, &BigUint::from(2u32)) {
+        return false;
+    }
+
+    strong_lucas_probable_prime(num)
+}
+
+/// Performs the strong Lucas probable prime test with Selfridge's parameters.
+///
+/// `num` is expected to be odd and greater than 37, and not divisible by any prime up to 37.
+fn strong_lucas_probable_prime(num: &BigUint) -> bool {
+    let d = match selfridge_parameter(num) {
+        Some(d) => d,
+        None => {
+            return false;
+        }
+    };
+
+    // Write num + 1 = 2^s * k with k odd
+    let num_plus_one = num + BigUint::one();
+    let s = get_trailing_zeros(&num_plus_one);
+    let k = &num_plus_one >> s.to_u64().unwrap();
+
+    let (u, mut v, mut q_k) = lucas_sequence(&k, d, num);
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+
+    // Check V_(k * 2^r) for 0 < r < s
+    let two = BigUint::from(2u32);
+    let mut r = BigUint::one();
+    while r < s {
+        v = sub_mod(&(&v * &v), &(&two * &q_k), num);
+        if v.is_zero() {
+            return true;
+        }
+        q_k = (&q_k * &q_k) % num;
+        r += BigUint::one();
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0: False
+        assert_eq!(baillie_psw(&BigUint::zero()), false);
+
+        // Test case 1: False
+        assert_eq!(baillie_psw(&BigUint::one()), false);
+
+        // Test case 2: True
+        assert_eq!(baillie_psw(&BigUint::from(2u32)), true);
+
+        // Test case 3: True
+        assert_eq!(baillie_psw(&BigUint::from(3u32)), true);
+
+        // Test case 4: False
+        assert_eq!(baillie_psw(&BigUint::from(4u32)), false);
+    }
+
+    #[test]
+    fn large_primes() {
+        let primes = [
+            // 10-12 digit primes
+            "871603259",
+            "98762051",
+            "1000000007",
+            "123575321",
+            "193818613",
+            "444444443",
+            "999999937",
+            "1000000000039",
+            "9999999929",
+            // Mersenne prime 2^127 - 1
+            "170141183460469231731687303715884105727",
+        ];
+
+        for prime in primes {
+            let prime = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+            assert_eq!(baillie_psw(&prime), true);
+        }
+    }
+
+    #[test]
+    fn carmichael_number() {
+        let carmichaels: Vec<BigUint> = vec![
+            BigUint::parse_bytes(b"561", 10).unwrap(),
+            BigUint::parse_bytes(b"41041", 10).unwrap(),
+            BigUint::parse_bytes(b"825265", 10).unwrap(),
+            BigUint::parse_bytes(b"321197185", 10).unwrap(),
+            BigUint::parse_bytes(b"5394826801", 10).unwrap(),
+            BigUint::parse_bytes(b"232250619601", 10).unwrap(),
+            BigUint::parse_bytes(b"9746347772161", 10).unwrap(),
+            BigUint::parse_bytes(b"1436697831295441", 10).unwrap(),
+            BigUint::parse_bytes(b"60977817398996785", 10).unwrap(),
+            BigUint::parse_bytes(b"7156857700403137441", 10).unwrap()
+        ];
+
+        for carmichael in carmichaels {
+            assert_eq!(baillie_psw(&carmichael), false);
+        }
+    }
+
+    #[test]
+    fn counter_example() {
+        // Strong pseudoprime to bases 2, 3, 5, 7 and 11
+        let counter_example = BigUint::parse_bytes(b"2152302898747", 10).unwrap();
+        assert_eq!(baillie_psw(&counter_example), false);
+
+        // Strong Lucas pseudoprimes are caught by the base 2 round
+        for n in [5459u32, 5777, 10877, 16109, 18971] {
+            assert_eq!(baillie_psw(&BigUint::from(n)), false);
+        }
+    }
+
+    #[test]
+    fn miller_rabin_bound() {
+        // Strong pseudoprime to every prime base up to 37
+        let pseudoprime = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+        assert_eq!(baillie_psw(&pseudoprime), false);
+    }
+}
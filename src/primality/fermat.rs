@@ -1,7 +1,8 @@
 use num_bigint::BigUint;
 use num_traits::One;
 use crate::operations::pow_mod;
-use crate::operations::gcd;
+use super::baillie_psw::baillie_psw;
+use super::generic;
 
 /// Performs a probabilistic primality test using Fermat's little theorem.
 ///
@@ -20,7 +21,8 @@ use crate::operations::gcd;
 /// # Returns
 ///
 /// * `true` if `num` passes the Fermat primality test for all witnesses.
-/// * `false` if `num` fails the test for any witness, or if `num` is less than or equal to 1.
+/// * `false` if `num` fails the test for any witness, if `num` is divisible by 2, 3 or 5 (other than those
+///   primes themselves), if `num` shares a factor with a smaller witness, or if `num` is less than or equal to 1.
 ///
 /// # Examples
 ///
@@ -34,35 +36,47 @@ use crate::operations::gcd;
 /// let non_prime = BigUint::parse_bytes(b"100", 10).unwrap();
 /// assert!(!fermat(&non_prime));
 /// ```
-
 pub fn fermat(num: &BigUint) -> bool {
-    // Fermat's little theorem test for witnesses 2, 3, 5, 7, 11, 13, 17, 19, 23, 29
+    generic::fermat(num)
+}
 
-    if *num <= BigUint::one() {
+/// Checks whether a number is a Fermat pseudoprime to a given base.
+///
+/// A Fermat pseudoprime to base `a` is a composite number `n` that still satisfies Fermat's little theorem,
+/// `a^(n-1) ≡ 1 (mod n)`. Such numbers are exactly the ones that fool `fermat` for that witness.
+/// Compositeness is decided with the Baillie-PSW test.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to check.
+/// * `base` - A reference to a `BigUint` representing the base `a`.
+///
+/// # Returns
+///
+/// * `true` if `n` is composite and `base^(n-1) ≡ 1 (mod n)`.
+/// * `false` if `n` is prime, less than or equal to 1, or fails the congruence.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::is_fermat_pseudoprime;
+///
+/// assert!(is_fermat_pseudoprime(&BigUint::from(341u32), &BigUint::from(2u32)));
+/// assert!(!is_fermat_pseudoprime(&BigUint::from(341u32), &BigUint::from(3u32)));
+/// ```
+pub fn is_fermat_pseudoprime(n: &BigUint, base: &BigUint) -> bool {
+    if *n <= BigUint::one() || baillie_psw(n) {
         return false;
     }
-
-    let switnesses = vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
-    let witnesses: Vec<BigUint> = switnesses
-        .iter()
-        .map(|x| BigUint::from(*x as u32))
-        .collect();
-    for witness in witnesses {
-        if gcd(&witness, num) != BigUint::one() {
-            continue;
-        }
-        let mod_value = pow_mod(&witness, &num, num);
-        let rhs = pow_mod(&witness, &BigUint::one(), &num);
-        if mod_value != rhs {
-            return false;
-        }
-    }
-    return true;
+    pow_mod(base, &(n - BigUint::one()), n) == BigUint::one()
 }
 
 #[cfg(test)]
 mod tests {
     use num_traits::Zero;
+    use crate::operations::gcd;
+    use crate::operations::utils::quick_composite_check;
 
     use super::*;
 
@@ -149,7 +163,45 @@ mod tests {
         ];
 
         for carmichael in carmichaels {
-            assert_eq!(fermat(&carmichael), true);
+            // Every one of these has a prime factor below 30, so a witness always shares a factor with it
+            assert_eq!(fermat(&carmichael), false);
+        }
+    }
+
+    #[test]
+    fn gcd_screen() {
+        // 41041 = 7 * 11 * 13 * 41 is a Carmichael number that passes quick_composite_check
+        let carmichael = BigUint::from(41041u32);
+        assert_eq!(quick_composite_check(&carmichael), None);
+        assert_eq!(fermat(&carmichael), false);
+
+        // 49 = 7 * 7 is rejected by witness 7
+        assert_eq!(fermat(&BigUint::from(49u32)), false);
+
+        // Witnesses equal to the number itself are skipped, not treated as factors
+        assert_eq!(fermat(&BigUint::from(7u32)), true);
+        assert_eq!(fermat(&BigUint::from(29u32)), true);
+    }
+
+    #[test]
+    fn fermat_pseudoprimes() {
+        let two = BigUint::from(2u32);
+        let three = BigUint::from(3u32);
+
+        // 341 = 11 * 31 fools base 2 but not base 3
+        assert_eq!(is_fermat_pseudoprime(&BigUint::from(341u32), &two), true);
+        assert_eq!(is_fermat_pseudoprime(&BigUint::from(341u32), &three), false);
+
+        // Primes are never pseudoprimes
+        assert_eq!(is_fermat_pseudoprime(&BigUint::from(997u32), &two), false);
+        assert_eq!(is_fermat_pseudoprime(&BigUint::one(), &two), false);
+
+        // Carmichael number 561 fools every coprime base
+        let carmichael = BigUint::from(561u32);
+        for base in 2..561u32 {
+            let base = BigUint::from(base);
+            let coprime = gcd(&base, &carmichael) == BigUint::one();
+            assert_eq!(is_fermat_pseudoprime(&carmichael, &base), coprime);
         }
     }
 }
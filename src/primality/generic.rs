@@ -0,0 +1,295 @@
+use num_bigint::BigUint;
+use num_integer::{ Integer, Roots };
+use crate::operations::{ gcd, pow_mod };
+use crate::operations::utils::isqrt;
+use super::standard::wheel_trial_division;
+use super::miller_rabin::{ strong_probable_prime, WITNESSES };
+
+/// The integer operations the generic primality tests are built on.
+///
+/// It is implemented for `u64`, where modular products are taken in `u128` and never overflow, and for
+/// `BigUint`. Numbers that fit in a machine word skip the heap allocations of `BigUint` altogether.
+pub trait PrimeInt: Clone + Ord + From<u32> {
+    /// Returns whether `divisor` divides `self`. `divisor` is expected to be nonzero.
+    fn is_divisible_by(&self, divisor: &Self) -> bool;
+
+    /// Returns `self + n`.
+    fn add_u32(&self, n: u32) -> Self;
+
+    /// Returns `self - 1`. `self` is expected to be nonzero.
+    fn minus_one(&self) -> Self;
+
+    /// Returns the integer square root of `self`.
+    fn isqrt(&self) -> Self;
+
+    /// Returns `(s, d)` such that `self = 2^s * d` with `d` odd. `self` is expected to be nonzero.
+    fn odd_part(&self) -> (u64, Self);
+
+    /// Returns `self * other mod modulus`.
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self;
+
+    /// Returns `self^exp mod modulus`.
+    fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self;
+
+    /// Returns the greatest common divisor of `self` and `other`.
+    fn gcd(&self, other: &Self) -> Self;
+}
+
+impl PrimeInt for u64 {
+    fn is_divisible_by(&self, divisor: &Self) -> bool {
+        (*self).is_multiple_of(*divisor)
+    }
+
+    fn add_u32(&self, n: u32) -> Self {
+        self + n as u64
+    }
+
+    fn minus_one(&self) -> Self {
+        self - 1
+    }
+
+    fn isqrt(&self) -> Self {
+        Roots::sqrt(self)
+    }
+
+    fn odd_part(&self) -> (u64, Self) {
+        let s = self.trailing_zeros();
+        (s as u64, self >> s)
+    }
+
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        (*self as u128 * *other as u128 % *modulus as u128) as u64
+    }
+
+    fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        let mut result = 1 % modulus;
+        let mut base = self % modulus;
+        let mut exp = *exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul_mod(&base, modulus);
+            }
+            base = base.mul_mod(&base, modulus);
+            exp >>= 1;
+        }
+        result
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        Integer::gcd(self, other)
+    }
+}
+
+impl PrimeInt for BigUint {
+    fn is_divisible_by(&self, divisor: &Self) -> bool {
+        self.is_multiple_of(divisor)
+    }
+
+    fn add_u32(&self, n: u32) -> Self {
+        self + n
+    }
+
+    fn minus_one(&self) -> Self {
+        self - 1u32
+    }
+
+    fn isqrt(&self) -> Self {
+        isqrt(self)
+    }
+
+    fn odd_part(&self) -> (u64, Self) {
+        let s = self.trailing_zeros().unwrap_or(0);
+        (s, self >> s)
+    }
+
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        (self * other) % modulus
+    }
+
+    fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        pow_mod(self, exp, modulus)
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        gcd(self, other)
+    }
+}
+
+/// Performs the standard primality test for any `PrimeInt`.
+///
+/// This is the trial division behind `large_primes::standard`, which calls it with a `BigUint`.
+///
+/// # Arguments
+///
+/// * `num` - A reference to the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` has no prime factors from 2 to sqrt(num).
+/// * `false` if `num` has a prime factor in that range or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::generic::standard;
+///
+/// assert!(standard(&1000000007u64));
+/// assert!(!standard(&1000000011u64));
+/// ```
+pub fn standard<T: PrimeInt>(num: &T) -> bool {
+    wheel_trial_division(num, || true).unwrap()
+}
+
+/// Performs the Fermat primality test for any `PrimeInt`.
+///
+/// This is the test behind `large_primes::fermat`, which calls it with a `BigUint`, and uses the same
+/// witnesses 2 through 29.
+///
+/// # Arguments
+///
+/// * `num` - A reference to the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` passes the Fermat primality test for all witnesses.
+/// * `false` if `num` fails the test for any witness, if `num` is divisible by 2, 3 or 5 (other than those
+///   primes themselves), if `num` shares a factor with a smaller witness, or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::generic::fermat;
+///
+/// assert!(fermat(&97u64));
+/// assert!(!fermat(&100u64));
+/// ```
+pub fn fermat<T: PrimeInt>(num: &T) -> bool {
+    if let Some(is_prime) = quick_composite_check(num) {
+        return is_prime;
+    }
+
+    let one = T::from(1u32);
+    let minus_one = num.minus_one();
+    for witness in [2u32, 3, 5, 7, 11, 13, 17, 19, 23, 29].map(T::from) {
+        if witness.gcd(num) != one {
+            // A smaller witness sharing a factor with `num` proves it composite
+            if witness < *num {
+                return false;
+            }
+            continue;
+        }
+        // Fermat's little theorem: a prime `num` satisfies a^(num-1) ≡ 1 (mod num) for every witness `a`
+        // coprime to it, so any other residue proves `num` composite
+        if witness.pow_mod(&minus_one, num) != one {
+            return false;
+        }
+    }
+    true
+}
+
+/// Performs the Miller-Rabin primality test for any `PrimeInt`.
+///
+/// This is the test behind `large_primes::miller_rabin`, which calls it with a `BigUint`. It uses the
+/// primes 2 through 37 as witnesses, so the result is deterministic for every `u64`.
+///
+/// # Arguments
+///
+/// * `num` - A reference to the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` passes the Miller-Rabin primality test for all witnesses.
+/// * `false` if `num` fails the test for any witness, if `num` is divisible by 2, 3 or 5 (other than those
+///   primes themselves), or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::generic::miller_rabin;
+///
+/// assert!(miller_rabin(&18446744073709551557u64));
+/// assert!(!miller_rabin(&3215031751u64));
+/// ```
+pub fn miller_rabin<T: PrimeInt>(num: &T) -> bool {
+    if let Some(is_prime) = quick_composite_check(num) {
+        return is_prime;
+    }
+
+    WITNESSES.map(T::from)
+        .iter()
+        .filter(|a| *a < num)
+        .all(|a| strong_probable_prime(num, a))
+}
+
+/// Settles numbers below 2 and multiples of 2, 3 and 5, like `utils::quick_composite_check`.
+fn quick_composite_check<T: PrimeInt>(num: &T) -> Option<bool> {
+    if *num < T::from(2u32) {
+        return Some(false);
+    }
+
+    for p in [2u32, 3, 5].map(T::from) {
+        if *num == p {
+            return Some(true);
+        }
+        if num.is_divisible_by(&p) {
+            return Some(false);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u64_edge_cases() {
+        for n in [0u64, 1, 4, 9, 25, 49] {
+            assert_eq!(standard(&n), false);
+            assert_eq!(fermat(&n), false);
+            assert_eq!(miller_rabin(&n), false);
+        }
+        for n in [2u64, 3, 5, 7, 29, 31, 37] {
+            assert_eq!(standard(&n), true);
+            assert_eq!(fermat(&n), true);
+            assert_eq!(miller_rabin(&n), true);
+        }
+    }
+
+    #[test]
+    fn u64_agrees_with_biguint() {
+        for n in 0..5000u64 {
+            let big = BigUint::from(n);
+            assert_eq!(standard(&n), standard(&big));
+            assert_eq!(fermat(&n), fermat(&big));
+            assert_eq!(miller_rabin(&n), miller_rabin(&big));
+        }
+
+        // Carmichael numbers fool Fermat for every coprime witness, but not Miller-Rabin
+        for n in [41041u64, 62745, 63973, 75361, 101101] {
+            assert_eq!(fermat(&n), fermat(&BigUint::from(n)));
+            assert_eq!(miller_rabin(&n), false);
+        }
+    }
+
+    #[test]
+    fn u64_near_max() {
+        // 2^64 - 59 is the largest 64 bit prime, and the products inside pow_mod must not overflow
+        let p = 18446744073709551557u64;
+        assert_eq!(miller_rabin(&p), true);
+        assert_eq!(fermat(&p), true);
+        assert_eq!(miller_rabin(&(p - 2)), false);
+        assert_eq!(miller_rabin(&u64::MAX), false);
+
+        // 4294967291 and 4294967279 are the two largest 32 bit primes
+        let n = 4294967291u64 * 4294967279;
+        assert_eq!(miller_rabin(&n), false);
+        assert_eq!(standard(&4294967291u64), true);
+
+        // Strong pseudoprimes to several small bases
+        for n in [3215031751u64, 2152302898747, 3474749660383, 341550071728321, 3825123056546413051] {
+            assert_eq!(miller_rabin(&n), false);
+        }
+    }
+}
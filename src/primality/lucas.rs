@@ -0,0 +1,206 @@
+use num_bigint::BigUint;
+use num_traits::{ One, Zero };
+use crate::operations::jacobi;
+use crate::operations::utils::isqrt;
+
+/// Performs the Lucas probable prime test with Selfridge's parameters.
+///
+/// For `P = 1` and `Q = (1 - D) / 4`, the Lucas sequence `U_0 = 0`, `U_1 = 1`, `U_m+1 = P U_m - Q U_m-1`
+/// satisfies `U_(p+1) ≡ 0 (mod p)` for every odd prime `p` with Jacobi symbol `(D/p) = -1`. Selfridge's
+/// method takes `D` as the first of 5, -7, 9, -11, ... with `(D/num) = -1`. This test fails on different
+/// composites than Fermat and Miller-Rabin: it rejects every Carmichael number below 10^6, although
+/// composites such as 323 = 17 * 19 pass it.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` is 2, or if `U_(num+1) ≡ 0 (mod num)`.
+/// * `false` if the congruence fails, if `num` is even (other than 2), a perfect square, or shares a factor
+///   with one of the `D` values tried, or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::{ fermat, lucas_probable_prime };
+///
+/// assert!(lucas_probable_prime(&BigUint::from(1000000007u32)));
+///
+/// // 410041 = 41 * 73 * 137 is a Carmichael number, which fools Fermat but not Lucas
+/// let carmichael = BigUint::from(410041u32);
+/// assert!(fermat(&carmichael));
+/// assert!(!lucas_probable_prime(&carmichael));
+/// ```
+pub fn lucas_probable_prime(num: &BigUint) -> bool {
+    if *num <= BigUint::one() {
+        return false;
+    }
+    if *num == BigUint::from(2u32) {
+        return true;
+    }
+    if !num.bit(0) {
+        return false;
+    }
+
+    match selfridge_parameter(num) {
+        Some(d) => lucas_sequence(&(num + 1u32), d, num).0.is_zero(),
+        None => false,
+    }
+}
+
+/// Finds Selfridge's `D`, the first of 5, -7, 9, -11, ... with Jacobi symbol `(D/num) = -1`.
+///
+/// `num` is expected to be odd and greater than 1. Returns `None` if `num` is a perfect square, where no such
+/// `D` exists, or if `num` shares a factor with a `D` tried before it, which proves it composite.
+pub(crate) fn selfridge_parameter(num: &BigUint) -> Option<i64> {
+    // Perfect squares have no `D` with (D/num) = -1
+    let root = isqrt(num);
+    if &root * &root == *num {
+        return None;
+    }
+
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(&signed_mod(d, num), num) {
+            -1 => {
+                return Some(d);
+            }
+            // `num` shares a factor with |D|, which is a proper factor unless `num` is |D| itself
+            0 if BigUint::from(d.unsigned_abs()) != *num => {
+                return None;
+            }
+            _ => {}
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+}
+
+/// Computes `U_k`, `V_k` and `Q^k` modulo `num` for the Lucas sequences with `P = 1` and `Q = (1 - d) / 4`.
+///
+/// `num` is expected to be odd, and `k` to be at least 1.
+pub(crate) fn lucas_sequence(k: &BigUint, d: i64, num: &BigUint) -> (BigUint, BigUint, BigUint) {
+    let p = BigUint::one();
+    let q = signed_mod((1 - d) / 4, num);
+    let d_mod = signed_mod(d, num);
+
+    // Walk the bits of k from the most significant one, starting from U_1, V_1 and Q^1
+    let mut u = BigUint::one();
+    let mut v = p.clone();
+    let mut q_k = q.clone();
+    let two = BigUint::from(2u32);
+    for bit in (0..k.bits() - 1).rev() {
+        // Doubling: U_2m = U_m * V_m, V_2m = V_m^2 - 2 Q^m
+        u = (&u * &v) % num;
+        v = sub_mod(&(&v * &v), &(&two * &q_k), num);
+        q_k = (&q_k * &q_k) % num;
+
+        if k.bit(bit) {
+            // Increment: U_m+1 = (P U_m + V_m) / 2, V_m+1 = (D U_m + P V_m) / 2
+            let new_u = half_mod(&(&p * &u + &v), num);
+            let new_v = half_mod(&(&d_mod * &u + &p * &v), num);
+            u = new_u;
+            v = new_v;
+            q_k = (&q_k * &q) % num;
+        }
+    }
+
+    (u, v, q_k)
+}
+
+/// Reduces a signed value into `[0, modulus)`.
+fn signed_mod(value: i64, modulus: &BigUint) -> BigUint {
+    let reduced = BigUint::from(value.unsigned_abs()) % modulus;
+    if value < 0 && !reduced.is_zero() {
+        modulus - reduced
+    } else {
+        reduced
+    }
+}
+
+/// Computes `(a - b) mod modulus` without going negative.
+pub(crate) fn sub_mod(a: &BigUint, b: &BigUint, modulus: &BigUint) -> BigUint {
+    let a = a % modulus;
+    let b = b % modulus;
+    if a >= b {
+        a - b
+    } else {
+        a + modulus - b
+    }
+}
+
+/// Computes `x / 2 mod modulus` for an odd modulus.
+fn half_mod(x: &BigUint, modulus: &BigUint) -> BigUint {
+    let x = x % modulus;
+    if x.bit(0) {
+        (x + modulus) >> 1u32
+    } else {
+        x >> 1u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primality::{ fermat, standard };
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0, 1: False
+        assert_eq!(lucas_probable_prime(&BigUint::zero()), false);
+        assert_eq!(lucas_probable_prime(&BigUint::one()), false);
+
+        // Test case 2, 3, 5, 11: True, where 5 and 11 are reached as |D| during the search
+        for p in [2u32, 3, 5, 11] {
+            assert_eq!(lucas_probable_prime(&BigUint::from(p)), true);
+        }
+
+        // Test case even and perfect squares: False
+        for n in [4u32, 100, 9, 25, 1009 * 1009] {
+            assert_eq!(lucas_probable_prime(&BigUint::from(n)), false);
+        }
+    }
+
+    #[test]
+    fn agrees_with_standard() {
+        // The Lucas pseudoprimes for Selfridge's parameters below 20000
+        let pseudoprimes = [
+            323u32, 377, 1159, 1829, 3827, 5459, 5777, 9071, 9179, 10877, 11419, 11663, 13919, 14839,
+            16109, 16211, 18407, 18971, 19043,
+        ];
+        for n in 0..20000u32 {
+            let expected = standard(&BigUint::from(n)) || pseudoprimes.contains(&n);
+            assert_eq!(lucas_probable_prime(&BigUint::from(n)), expected);
+        }
+    }
+
+    #[test]
+    fn carmichael_numbers() {
+        // Carmichael numbers pass Fermat for every coprime witness, but none below 10^6 passes Lucas
+        let carmichael = [
+            561u32, 1105, 1729, 2465, 2821, 6601, 8911, 10585, 15841, 29341, 41041, 46657, 52633, 62745,
+            63973, 75361, 101101, 115921, 126217, 162401, 172081, 188461, 252601, 278545, 294409, 314821,
+            334153, 340561, 399001, 410041, 449065, 488881, 512461, 530881, 552721, 656601, 658801, 670033,
+            748657, 825265, 838201, 852841, 997633,
+        ];
+        for n in carmichael {
+            assert_eq!(lucas_probable_prime(&BigUint::from(n)), false);
+        }
+
+        // The ones without a factor up to 29 share no factor with the witnesses of `fermat`, and pass it
+        for n in [252601u32, 294409, 399001, 410041, 488881, 512461] {
+            assert_eq!(fermat(&BigUint::from(n)), true);
+            assert_eq!(lucas_probable_prime(&BigUint::from(n)), false);
+        }
+    }
+
+    #[test]
+    fn large_primes() {
+        // Mersenne prime 2^127 - 1
+        let mersenne = (BigUint::one() << 127u32) - 1u32;
+        assert_eq!(lucas_probable_prime(&mersenne), true);
+        assert_eq!(lucas_probable_prime(&(&mersenne + 2u32)), false);
+    }
+}
@@ -1,6 +1,7 @@
 use num_bigint::BigUint;
 use num_traits::{ One, Zero };
-use crate::operations::pow;
+use crate::operations::{ pow, pow_mod };
+use super::miller_rabin;
 
 /// Performs the Lucas-Lehmer test for Mersenne primes.
 ///
@@ -8,6 +9,10 @@ use crate::operations::pow;
 /// where p is a prime number. This function checks if the Mersenne number corresponding to the given power
 /// is prime.
 ///
+/// 2^p - 1 can only be prime when `p` is prime, since 2^a - 1 divides 2^(ab) - 1. A composite `power` is
+/// therefore rejected with `miller_rabin` before the expensive Lucas-Lehmer loop, which is only valid for
+/// prime exponents. The result says nothing about numbers other than Mersenne numbers.
+///
 /// # Arguments
 ///
 /// * `power` - A reference to a `BigUint` representing the power `p` in the Mersenne number 2^p - 1.
@@ -15,7 +20,7 @@ use crate::operations::pow;
 /// # Returns
 ///
 /// * `true` if the Mersenne number 2^p - 1 is prime.
-/// * `false` if the number is composite or if `power` is less than or equal to 1. Note that for power = 2, the function returns `true` as 2^2 - 1 = 3 is prime.
+/// * `false` if the number is composite, if `power` is composite, or if `power` is less than or equal to 1. Note that for power = 2, the function returns `true` as 2^2 - 1 = 3 is prime.
 ///
 /// # Examples
 ///
@@ -36,6 +41,9 @@ pub fn lucas_lehmer_test(power: &BigUint) -> bool {
     if power == &BigUint::from(2u32) {
         return true;
     }
+    if !miller_rabin(power) {
+        return false;
+    }
 
     let mersenne = pow(&BigUint::from(2u32), power) - BigUint::one();
     let mut sum = BigUint::from(4u32);
@@ -49,6 +57,63 @@ pub fn lucas_lehmer_test(power: &BigUint) -> bool {
     sum == BigUint::zero()
 }
 
+/// Searches for a small factor of the Mersenne number 2^p - 1 by trial factoring.
+///
+/// Every prime factor `q` of 2^p - 1 (for an odd prime `p`) has the form `2kp + 1` and satisfies
+/// `q ≡ ±1 (mod 8)`, so only those candidates are tried. Divisibility is verified with
+/// `pow_mod(2, p, q) == 1`, without ever building the Mersenne number itself. This is much cheaper than
+/// a full Lucas-Lehmer test and quickly rules out many composite Mersenne numbers.
+///
+/// # Arguments
+///
+/// * `p` - A `u64` representing the exponent `p` in the Mersenne number 2^p - 1. `p` must be prime, since the
+///   candidate form above does not hold otherwise.
+/// * `bound` - A `u64` representing the largest candidate factor to try.
+///
+/// # Returns
+///
+/// * `Some(factor)` with the smallest proper factor of 2^p - 1 not larger than `bound`.
+/// * `None` if no such factor exists, or if `p` is not prime.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::mersenne_trial_factor;
+///
+/// // 2^11 - 1 = 2047 = 23 * 89
+/// assert_eq!(mersenne_trial_factor(11, 100), Some(BigUint::from(23u32)));
+///
+/// // 2^13 - 1 = 8191 is prime
+/// assert_eq!(mersenne_trial_factor(13, 100), None);
+/// ```
+pub fn mersenne_trial_factor(p: u64, bound: u64) -> Option<BigUint> {
+    if !miller_rabin(&BigUint::from(p)) {
+        return None;
+    }
+
+    let two = BigUint::from(2u32);
+    let exp = BigUint::from(p);
+    let step = 2 * (p as u128);
+
+    let mut q = step + 1;
+    while q <= bound as u128 {
+        // The factor must be smaller than 2^p - 1 itself
+        if p < 64 && q >= (1u128 << p) - 1 {
+            break;
+        }
+        if q % 8 == 1 || q % 8 == 7 {
+            let factor = BigUint::from(q);
+            if pow_mod(&two, &exp, &factor) == BigUint::one() {
+                return Some(factor);
+            }
+        }
+        q += step;
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +165,35 @@ mod tests {
             assert!(!lucas_lehmer_test(&BigUint::from(power as u32)));
         }
     }
+
+    #[test]
+    fn composite_exponent() {
+        // 2^4 - 1 = 15, 2^9 - 1 = 511 = 7 * 73
+        assert!(!lucas_lehmer_test(&BigUint::from(4u32)));
+        assert!(!lucas_lehmer_test(&BigUint::from(9u32)));
+
+        // The loop would square a million-bit number a million times, the exponent check returns at once
+        assert!(!lucas_lehmer_test(&BigUint::from(1000u32)));
+        assert!(!lucas_lehmer_test(&BigUint::from(1000000u32)));
+    }
+
+    #[test]
+    fn trial_factor() {
+        // 2^11 - 1 = 2047 = 23 * 89
+        assert_eq!(mersenne_trial_factor(11, 1000), Some(BigUint::from(23u32)));
+
+        // 2^23 - 1 = 47 * 178481
+        assert_eq!(mersenne_trial_factor(23, 1000), Some(BigUint::from(47u32)));
+
+        // Mersenne primes have no proper factor
+        assert_eq!(mersenne_trial_factor(7, 1000), None);
+        assert_eq!(mersenne_trial_factor(31, 100000), None);
+
+        // Bound below the smallest factor
+        assert_eq!(mersenne_trial_factor(11, 22), None);
+
+        // Composite exponents are rejected, although 2^4 - 1 = 3 * 5 and 2^9 - 1 = 7 * 73
+        assert_eq!(mersenne_trial_factor(4, 1000), None);
+        assert_eq!(mersenne_trial_factor(9, 1000), None);
+    }
 }
@@ -1,14 +1,22 @@
+use alloc::vec::Vec;
 use num_bigint::BigUint;
+use rand::{ RngCore, SeedableRng };
+use rand::rngs::StdRng;
 use num_traits::One;
 use num_traits::Zero;
-use crate::operations::{ pow, pow_mod };
-use crate::operations::utils::get_trailing_zeros;
+use crate::operations::utils::{ quick_composite_check, random_base };
+#[cfg(feature = "std")]
+use crate::generators::get_max_primes_u64;
+use super::baillie_psw::baillie_psw;
+use super::generic::{ self, PrimeInt };
 
 /// Performs the Miller-Rabin primality test.
 ///
 /// The Miller-Rabin test is a probabilistic primality test: it can prove that a number is composite,
-/// but it cannot prove that a number is prime. This implementation uses a set of deterministic
-/// witnesses for numbers less than 2^64, which are 2, 3, 5, 7, 11.
+/// but it cannot prove that a number is prime. This implementation uses the first twelve primes,
+/// 2 through 37, as witnesses. No composite below 318665857834031151167461 (about 3.2 * 10^23, well
+/// above 2^64) is a strong pseudoprime to all of them, so the result is deterministic in that range.
+/// Above it the result is probable only; use `baillie_psw` for larger numbers.
 ///
 /// # Arguments
 ///
@@ -17,7 +25,8 @@ use crate::operations::utils::get_trailing_zeros;
 /// # Returns
 ///
 /// * `true` if `num` passes the Miller-Rabin primality test for all witnesses.
-/// * `false` if `num` fails the test for any witness, or if `num` is less than or equal to 1.
+/// * `false` if `num` fails the test for any witness, if `num` is divisible by 2, 3 or 5 (other than those
+///   primes themselves), or if `num` is less than or equal to 1.
 ///   Note that the function returns `true` when `num` is 2, as it is the only even prime number.
 ///
 /// # Examples
@@ -35,29 +44,75 @@ use crate::operations::utils::get_trailing_zeros;
 ///
 /// # Note
 ///
-/// This implementation assumes the presence of other functions like `get_trailing_zeros` and `pow_mod`
-/// for getting the number of trailing zeros in the binary representation of a number and for
-/// performing modular exponentiation, respectively. Ensure these functions are correctly implemented.
+/// Each witness costs one `pow_mod` for `a^d`, followed by at most `s - 1` modular squarings, where
+/// `num - 1 = 2^s * d` with `d` odd.
 pub fn miller_rabin(num: &BigUint) -> bool {
-    // Miller Rabin test for witnesses 2, 3, 5, 7, 11, 13, 17, 19, 23, 29
+    generic::miller_rabin(num)
+}
 
-    if *num <= BigUint::one() {
-        return false;
-    }
+/// The witnesses used by `miller_rabin`, the primes 2 through 37.
+pub(crate) const WITNESSES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
 
-    if *num == BigUint::from(2u32) {
-        return true;
-    }
+/// Performs the Miller-Rabin primality test, returning the witness that proves a composite.
+///
+/// This runs the same test as `miller_rabin`, so its result is deterministic below the same bound.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+///
+/// # Returns
+///
+/// * `Ok(())` if `num` passes the test for all witnesses.
+/// * `Err(Some(witness))` with the first base for which `num` is not a strong probable prime. For numbers
+///   divisible by 2, 3 or 5 (other than those primes themselves) this is that small prime factor.
+/// * `Err(None)` if `num` is 0 or 1, which are not prime but not composite either, so have no witness.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::miller_rabin_witness;
+///
+/// assert_eq!(miller_rabin_witness(&BigUint::from(1000000007u32)), Ok(()));
+///
+/// // 2047 = 23 * 89 fools base 2, but not base 3
+/// assert_eq!(miller_rabin_witness(&BigUint::from(2047u32)), Err(Some(BigUint::from(3u32))));
+///
+/// // 1 is not prime, but no base proves it composite
+/// assert_eq!(miller_rabin_witness(&BigUint::from(1u32)), Err(None));
+/// ```
+pub fn miller_rabin_witness(num: &BigUint) -> Result<(), Option<BigUint>> {
+    miller_rabin_witness_with(num, &WITNESSES)
+}
 
-    // Get r and d such that num = 2^r * d + 1
-    let one_minus_num: BigUint = num - BigUint::one();
-    let s: &BigUint = &get_trailing_zeros(&one_minus_num);
-    let d: &BigUint = &(one_minus_num / pow(&BigUint::from(2u32), s));
+/// Runs the Miller-Rabin test against the given set of small witnesses, skipping any witness
+/// that is not smaller than `num`.
+#[cfg(feature = "std")]
+pub(crate) fn miller_rabin_with_witnesses(num: &BigUint, switnesses: &[u32]) -> bool {
+    miller_rabin_witness_with(num, switnesses).is_ok()
+}
+
+/// Runs the Miller-Rabin test against the given set of small witnesses, returning the first failing one.
+fn miller_rabin_witness_with(num: &BigUint, switnesses: &[u32]) -> Result<(), Option<BigUint>> {
+    match quick_composite_check(num) {
+        Some(true) => {
+            return Ok(());
+        }
+        Some(false) => {
+            // 0 and 1 fail without being composite, so they have no witness
+            let small_factor = [2u32, 3, 5]
+                .iter()
+                .map(|p| BigUint::from(*p))
+                .find(|p| *num > BigUint::one() && (num % p).is_zero());
+            return Err(small_factor);
+        }
+        None => {}
+    }
 
-    let switnesses = vec![2, 3, 5, 7, 11];
     let witnesses: Vec<BigUint> = switnesses
         .iter()
-        .map(|x| BigUint::from(*x as u32))
+        .map(|x| BigUint::from(*x))
         .collect();
 
     for a in witnesses {
@@ -65,36 +120,324 @@ pub fn miller_rabin(num: &BigUint) -> bool {
             continue;
         }
 
-        // First Sub Test
-        if pow_mod(&a, &d, num) == BigUint::one() {
-            continue;
+        if !strong_probable_prime(num, &a) {
+            return Err(Some(a));
         }
+    }
 
-        // Second Sub Test
-        let mut found = false;
-        let mut r = BigUint::zero();
-        while r < *s {
-            let a_power = d * pow(&BigUint::from(2u32), &r);
-            if (pow_mod(&a, &a_power, num) + BigUint::one()) % num == BigUint::zero() {
-                found = true;
-                break;
-            }
-            r = r + BigUint::one();
+    Ok(())
+}
+
+/// Performs the Miller-Rabin primality test with randomly chosen witnesses.
+///
+/// Each round draws a witness uniformly from `[2, num - 2]` using the given random number generator.
+/// A composite number passes a single round with probability at most 1/4, so passing `rounds` rounds
+/// leaves an error probability of at most `4^-rounds`. Given the same generator state, the same witnesses
+/// and verdict are produced.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `rounds` - The number of random witnesses to try.
+/// * `rng` - The random number generator used to draw the witnesses.
+///
+/// # Returns
+///
+/// * `true` if `num` passes the strong probable prime test for every drawn witness.
+/// * `false` if `num` fails the test for any witness, if `num` is divisible by 2, 3 or 5 (other than those
+///   primes themselves), or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+/// use large_primes::miller_rabin_probabilistic;
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// assert!(miller_rabin_probabilistic(&BigUint::from(1000000007u32), 10, &mut rng));
+/// assert!(!miller_rabin_probabilistic(&BigUint::from(1000000011u32), 10, &mut rng));
+/// ```
+pub fn miller_rabin_probabilistic(num: &BigUint, rounds: usize, rng: &mut impl RngCore) -> bool {
+    if let Some(is_prime) = quick_composite_check(num) {
+        return is_prime;
+    }
+
+    random_witnesses(num, rounds, rng)
+        .iter()
+        .all(|a| strong_probable_prime(num, a))
+}
+
+/// Performs the randomized Miller-Rabin test with a fresh seed, returning the seed alongside the verdict.
+///
+/// The returned seed can be fed to `StdRng::seed_from_u64` and passed to `miller_rabin_probabilistic`
+/// to replay exactly the same witnesses, which helps reproducing a surprising result.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `rounds` - The number of random witnesses to try.
+///
+/// # Returns
+///
+/// A tuple `(is_prime, seed)` with the verdict of `miller_rabin_probabilistic` and the seed used.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+/// use large_primes::{ miller_rabin_random_seeded, miller_rabin_probabilistic };
+///
+/// let number = BigUint::from(1000000007u32);
+/// let (is_prime, seed) = miller_rabin_random_seeded(&number, 10);
+///
+/// let mut rng = StdRng::seed_from_u64(seed);
+/// assert_eq!(miller_rabin_probabilistic(&number, 10, &mut rng), is_prime);
+/// ```
+#[cfg(feature = "std")]
+pub fn miller_rabin_random_seeded(num: &BigUint, rounds: usize) -> (bool, u64) {
+    let seed: u64 = rand::random();
+    let mut rng = StdRng::seed_from_u64(seed);
+    (miller_rabin_probabilistic(num, rounds, &mut rng), seed)
+}
+
+/// Performs the Miller-Rabin test with `k` random witnesses drawn from the thread-local generator.
+///
+/// Each round lets a composite through with probability at most 1/4, so the error bound is `4^-k`. This
+/// suits numbers far above the range where the fixed witnesses of `miller_rabin` are deterministic.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `k` - The number of random witnesses to try.
+///
+/// # Returns
+///
+/// * `true` if `num` passes the test for all `k` witnesses.
+/// * `false` if `num` fails the test for any witness, if `num` is divisible by 2, 3 or 5 (other than those
+///   primes themselves), or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::miller_rabin_rounds;
+///
+/// let mersenne = (BigUint::from(1u32) << 127u32) - BigUint::from(1u32);
+/// assert!(miller_rabin_rounds(&mersenne, 20));
+/// ```
+#[cfg(feature = "std")]
+pub fn miller_rabin_rounds(num: &BigUint, k: usize) -> bool {
+    miller_rabin_probabilistic(num, k, &mut rand::thread_rng())
+}
+
+/// Performs the Miller-Rabin test with `k` random witnesses drawn from a generator seeded with `seed`.
+///
+/// The same seed always draws the same witnesses, which makes the result reproducible.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `k` - The number of random witnesses to try.
+/// * `seed` - A `u64` seeding the random number generator.
+///
+/// # Returns
+///
+/// The same verdict as `miller_rabin_rounds`, for the witnesses drawn from `seed`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::miller_rabin_rounds_seeded;
+///
+/// assert!(miller_rabin_rounds_seeded(&BigUint::from(1000000007u32), 10, 42));
+/// assert!(!miller_rabin_rounds_seeded(&BigUint::from(1000000011u32), 10, 42));
+/// ```
+pub fn miller_rabin_rounds_seeded(num: &BigUint, k: usize, seed: u64) -> bool {
+    let mut rng = StdRng::seed_from_u64(seed);
+    miller_rabin_probabilistic(num, k, &mut rng)
+}
+
+/// Finds the smallest base that proves a number composite with the strong probable prime test.
+///
+/// Bases 2, 3, 4, ... are tried in order. Primality is settled with `baillie_psw` first, so the search
+/// only runs for composites, where at least three quarters of all bases are witnesses.
+///
+/// # Arguments
+///
+/// * `n` - A reference to a `BigUint` representing the number to examine.
+///
+/// # Returns
+///
+/// * `Some(base)` with the least Miller-Rabin witness if `n` is composite.
+/// * `None` if `n` is prime, or if `n` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::least_mr_witness;
+///
+/// // 2047 = 23 * 89 is a strong pseudoprime to base 2
+/// assert_eq!(least_mr_witness(&BigUint::from(2047u32)), Some(BigUint::from(3u32)));
+/// assert_eq!(least_mr_witness(&BigUint::from(2039u32)), None);
+/// ```
+pub fn least_mr_witness(n: &BigUint) -> Option<BigUint> {
+    if *n < BigUint::from(2u32) || baillie_psw(n) {
+        return None;
+    }
+
+    let mut base = BigUint::from(2u32);
+    while strong_probable_prime(n, &base) {
+        base += BigUint::one();
+    }
+    Some(base)
+}
+
+/// Measures how often a set of Miller-Rabin witnesses wrongly calls a composite number prime.
+///
+/// Every composite `n` up to `max` is tested against each witness smaller than `n`, and counted when it is a
+/// strong probable prime to all of them. Which numbers are composite is taken from the sieve, so the result
+/// does not rely on any primality test. Witnesses that are not smaller than `n` are skipped, as in
+/// `miller_rabin`.
+///
+/// # Arguments
+///
+/// * `witnesses` - A slice of `BigUint` bases making up the witness set.
+/// * `max` - A `u64` representing the largest number checked.
+///
+/// # Returns
+///
+/// The number of composites up to `max` that pass every witness, divided by the number of composites up to
+/// `max`. Returns 0.0 if there are no composites up to `max`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::pseudoprime_density;
+///
+/// // 2047 = 23 * 89 is the only strong pseudoprime to base 2 below 3000
+/// assert!(pseudoprime_density(&[BigUint::from(2u32)], 3000) > 0.0);
+/// assert_eq!(pseudoprime_density(&[BigUint::from(2u32)], 2000), 0.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn pseudoprime_density(witnesses: &[BigUint], max: u64) -> f64 {
+    let mut is_prime = vec![false; max.saturating_add(1) as usize];
+    for p in get_max_primes_u64(max) {
+        is_prime[p as usize] = true;
+    }
+
+    let mut composites = 0u64;
+    let mut fooled = 0u64;
+    for n in 4..=max {
+        if is_prime[n as usize] {
+            continue;
+        }
+        composites += 1;
+        let n = BigUint::from(n);
+        if witnesses.iter().filter(|a| **a < n).all(|a| strong_probable_prime(&n, a)) {
+            fooled += 1;
         }
+    }
+
+    if composites == 0 {
+        return 0.0;
+    }
+    (fooled as f64) / (composites as f64)
+}
+
+/// Performs the Miller-Rabin primality test on many numbers at once, spread across threads.
+///
+/// Each number is tested independently with `miller_rabin`, using `rayon`'s parallel iterators, so the
+/// verdicts are identical to calling `miller_rabin` on each number in turn.
+///
+/// This function is only available with the `parallel` feature enabled.
+///
+/// # Arguments
+///
+/// * `nums` - A slice of `BigUint` values to test for primality.
+///
+/// # Returns
+///
+/// A vector holding the verdict of `miller_rabin` for each number, in the same order as `nums`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::miller_rabin_batch;
+///
+/// let nums = vec![BigUint::from(2047u32), BigUint::from(1000000007u32)];
+/// assert_eq!(miller_rabin_batch(&nums), vec![false, true]);
+/// ```
+#[cfg(feature = "parallel")]
+pub fn miller_rabin_batch(nums: &[BigUint]) -> Vec<bool> {
+    use rayon::prelude::*;
+
+    nums.par_iter()
+        .map(miller_rabin)
+        .collect()
+}
+
+/// Draws `rounds` witnesses uniformly from `[2, num - 2]`. `num` is expected to be at least 5.
+fn random_witnesses(num: &BigUint, rounds: usize, rng: &mut impl RngCore) -> Vec<BigUint> {
+    (0..rounds).map(|_| random_base(num, rng)).collect()
+}
+
+/// Checks whether `num` is a strong probable prime to base `a`.
+///
+/// `num` is expected to be greater than 2, and `a` to lie in `[2, num - 1]`.
+pub(crate) fn strong_probable_prime<T: PrimeInt>(num: &T, a: &T) -> bool {
+    // Get s and d such that num = 2^s * d + 1 with d odd
+    let one = T::from(1u32);
+    let minus_one = num.minus_one();
+    let (s, d) = minus_one.odd_part();
+
+    // First Sub Test: a^d = 1 or a^d = -1
+    let mut x = counted_pow_mod(a, &d, num);
+    if x == one || x == minus_one {
+        return true;
+    }
 
-        if !found {
-            println!("Miller Rabin test failed for {}, witness {}", num, a);
+    // Second Sub Test: a^(d * 2^r) = -1 for some 0 < r < s, squaring the previous residue each time
+    for _ in 1..s {
+        x = x.mul_mod(&x, num);
+        if x == minus_one {
+            return true;
+        }
+        // Once the residue is 1, squaring keeps it at 1 and never reaches -1
+        if x == one {
             return false;
         }
     }
 
-    return true;
+    false
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of modular exponentiations done by `strong_probable_prime` on the current thread.
+    static MODEXP_CALLS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+/// Calls `PrimeInt::pow_mod`, counting the calls in tests.
+fn counted_pow_mod<T: PrimeInt>(base: &T, exp: &T, modulus: &T) -> T {
+    #[cfg(test)]
+    MODEXP_CALLS.with(|calls| calls.set(calls.get() + 1));
+    base.pow_mod(exp, modulus)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::generators::get_max_primes;
+    use crate::operations::{ pow, pow_mod };
+    use crate::operations::utils::get_trailing_zeros;
 
     #[test]
     fn edge_cases() {
@@ -174,7 +517,159 @@ mod tests {
 
     #[test]
     fn counter_example() {
+        // Strong pseudoprime to bases 2, 3, 5, 7 and 11, caught by base 13
         let counter_example = BigUint::parse_bytes(b"2152302898747", 10).unwrap();
-        assert_eq!(miller_rabin(&counter_example), true);
+        assert_eq!(miller_rabin(&counter_example), false);
+
+        // The smallest strong pseudoprime to all twelve witnesses marks the deterministic bound
+        let bound = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+        assert_eq!(miller_rabin(&bound), true);
+    }
+
+    #[test]
+    fn random_seeded_replay() {
+        let numbers = ["1000000007", "1000000011", "7156857700403137441"];
+
+        for number in numbers {
+            let number = BigUint::parse_bytes(number.as_bytes(), 10).unwrap();
+            let (is_prime, seed) = miller_rabin_random_seeded(&number, 8);
+
+            let mut first = StdRng::seed_from_u64(seed);
+            let mut second = StdRng::seed_from_u64(seed);
+            assert_eq!(
+                random_witnesses(&number, 8, &mut first),
+                random_witnesses(&number, 8, &mut second)
+            );
+
+            let mut replay = StdRng::seed_from_u64(seed);
+            assert_eq!(miller_rabin_probabilistic(&number, 8, &mut replay), is_prime);
+        }
+    }
+
+    #[test]
+    fn least_witness() {
+        assert_eq!(least_mr_witness(&BigUint::from(2047u32)), Some(BigUint::from(3u32)));
+        assert_eq!(least_mr_witness(&BigUint::from(15u32)), Some(BigUint::from(2u32)));
+        assert_eq!(least_mr_witness(&BigUint::from(4u32)), Some(BigUint::from(2u32)));
+
+        // 3215031751 is a strong pseudoprime to bases 2, 3, 5 and 7
+        assert_eq!(least_mr_witness(&BigUint::from(3215031751u64)), Some(BigUint::from(11u32)));
+
+        assert_eq!(least_mr_witness(&BigUint::from(2u32)), None);
+        assert_eq!(least_mr_witness(&BigUint::from(1000000007u32)), None);
+        assert_eq!(least_mr_witness(&BigUint::one()), None);
+    }
+
+    #[test]
+    fn failing_witness() {
+        assert_eq!(miller_rabin_witness(&BigUint::from(97u32)), Ok(()));
+        assert_eq!(miller_rabin_witness(&BigUint::from(2u32)), Ok(()));
+
+        assert_eq!(miller_rabin_witness(&BigUint::from(2047u32)), Err(Some(BigUint::from(3u32))));
+        assert_eq!(miller_rabin_witness(&BigUint::from(3215031751u64)), Err(Some(BigUint::from(11u32))));
+        let counter_example = BigUint::parse_bytes(b"2152302898747", 10).unwrap();
+        assert_eq!(miller_rabin_witness(&counter_example), Err(Some(BigUint::from(13u32))));
+
+        // Small prime factors are reported directly
+        assert_eq!(miller_rabin_witness(&BigUint::from(91u32 * 5)), Err(Some(BigUint::from(5u32))));
+        assert_eq!(miller_rabin_witness(&BigUint::from(1000u32)), Err(Some(BigUint::from(2u32))));
+        assert_eq!(miller_rabin_witness(&BigUint::one()), Err(None));
+        assert_eq!(miller_rabin_witness(&BigUint::zero()), Err(None));
+    }
+
+    #[test]
+    fn random_rounds() {
+        let primes = ["1000000007", "1000000000039", "170141183460469231731687303715884105727"];
+        let composites = [
+            "2152302898747",
+            "3215031751",
+            "318665857834031151167461",
+            "7156857700403137441",
+            // (2^61 - 1) * (2^89 - 1)
+            "1427247692705959880439315947500961989719490561",
+        ];
+
+        for seed in 0..8 {
+            for prime in primes {
+                let prime = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+                assert_eq!(miller_rabin_rounds_seeded(&prime, 16, seed), true);
+            }
+            for composite in composites {
+                let composite = BigUint::parse_bytes(composite.as_bytes(), 10).unwrap();
+                assert_eq!(miller_rabin_rounds_seeded(&composite, 16, seed), false);
+            }
+        }
+
+        // The same seed replays the same verdict
+        let number = BigUint::parse_bytes(b"1000000000039", 10).unwrap();
+        assert_eq!(miller_rabin_rounds_seeded(&number, 4, 7), miller_rabin_rounds_seeded(&number, 4, 7));
+        assert_eq!(miller_rabin_rounds(&number, 16), true);
+        assert_eq!(miller_rabin_rounds(&BigUint::one(), 16), false);
+    }
+
+    #[test]
+    fn witness_set_density() {
+        let to_big = |values: &[u32]| -> Vec<BigUint> { values.iter().map(|&v| BigUint::from(v)).collect() };
+
+        // 2047 = 23 * 89 is the only strong pseudoprime to base 2 up to 3000, out of 2569 composites
+        let density = pseudoprime_density(&to_big(&[2]), 3000);
+        assert!(density > 0.0);
+        assert!((density - 1.0 / 2569.0).abs() < 1e-12);
+
+        assert_eq!(pseudoprime_density(&to_big(&[2, 3, 5, 7, 11]), 3000), 0.0);
+
+        // No composites at all
+        assert_eq!(pseudoprime_density(&to_big(&[2]), 3), 0.0);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn batch_agrees() {
+        let primes = get_max_primes(1000);
+        let serial: Vec<bool> = primes.iter().map(miller_rabin).collect();
+        assert_eq!(miller_rabin_batch(&primes), serial);
+
+        let nums: Vec<BigUint> = (0..1000u32).map(BigUint::from).collect();
+        let serial: Vec<bool> = nums.iter().map(miller_rabin).collect();
+        assert_eq!(miller_rabin_batch(&nums), serial);
+        assert_eq!(miller_rabin_batch(&[]), Vec::<bool>::new());
+    }
+
+    /// The strong probable prime test computing a fresh `pow_mod` for every `r`, kept as a reference.
+    fn strong_probable_prime_reference(num: &BigUint, a: &BigUint) -> bool {
+        let one_minus_num: BigUint = num - BigUint::one();
+        let s = get_trailing_zeros(&one_minus_num);
+        let d = &one_minus_num / pow(&BigUint::from(2u32), &s);
+        if pow_mod(a, &d, num) == BigUint::one() {
+            return true;
+        }
+        let mut r = BigUint::zero();
+        while r < s {
+            let a_power = &d * pow(&BigUint::from(2u32), &r);
+            if (pow_mod(a, &a_power, num) + BigUint::one()) % num == BigUint::zero() {
+                return true;
+            }
+            r += BigUint::one();
+        }
+        false
+    }
+
+    #[test]
+    fn single_modexp() {
+        let mut numbers: Vec<BigUint> = (5..20000u32).step_by(2).map(BigUint::from).collect();
+        for n in ["2152302898747", "3215031751", "318665857834031151167461", "7156857700403137441"] {
+            numbers.push(BigUint::parse_bytes(n.as_bytes(), 10).unwrap());
+        }
+        // 2^64 + 1 has s = 64, where the reference needs up to 65 exponentiations
+        numbers.push((BigUint::one() << 64u32) + BigUint::one());
+
+        for n in &numbers {
+            for a in WITNESSES.iter().map(|&a| BigUint::from(a)).filter(|a| a < n) {
+                MODEXP_CALLS.with(|calls| calls.set(0));
+                let verdict = strong_probable_prime(n, &a);
+                assert_eq!(MODEXP_CALLS.with(|calls| calls.get()), 1);
+                assert_eq!(verdict, strong_probable_prime_reference(n, &a));
+            }
+        }
     }
 }
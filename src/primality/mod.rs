@@ -2,8 +2,36 @@ pub mod standard;
 pub mod fermat;
 pub mod miller_rabin;
 pub mod lucas_lehmer;
+pub mod baillie_psw;
+#[cfg(feature = "std")]
+pub mod auto;
+pub mod wagstaff;
+pub mod solovay_strassen;
+pub mod generic;
+pub mod result;
+pub mod aks;
+pub mod lucas;
 
 pub use standard::standard;
-pub use fermat::fermat;
-pub use miller_rabin::miller_rabin;
-pub use lucas_lehmer::lucas_lehmer_test;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub use standard::standard_within_budget;
+#[cfg(feature = "parallel")]
+pub use standard::standard_parallel;
+#[cfg(feature = "parallel")]
+pub use miller_rabin::miller_rabin_batch;
+pub use fermat::{ fermat, is_fermat_pseudoprime };
+pub use miller_rabin::{ miller_rabin, miller_rabin_witness, least_mr_witness, miller_rabin_probabilistic, miller_rabin_rounds_seeded };
+#[cfg(feature = "std")]
+pub use miller_rabin::{ pseudoprime_density, miller_rabin_random_seeded, miller_rabin_rounds };
+pub use lucas_lehmer::{ lucas_lehmer_test, mersenne_trial_factor };
+#[cfg(feature = "std")]
+pub use auto::{ is_prime, is_prime_with_policy, is_prime_recommended, is_likely_prime, race_primality, export_test_corpus, Accuracy, PrimalityPolicy, CompositeBloom };
+pub use baillie_psw::baillie_psw;
+pub use wagstaff::is_wagstaff_prime;
+pub use solovay_strassen::solovay_strassen_or_exact;
+#[cfg(feature = "std")]
+pub use solovay_strassen::solovay_strassen;
+pub use generic::PrimeInt;
+pub use result::PrimalityResult;
+pub use aks::aks;
+pub use lucas::lucas_probable_prime;
\ No newline at end of file
@@ -0,0 +1,73 @@
+use alloc::string::String;
+use num_bigint::BigUint;
+
+/// The outcome of running a primality test on a number.
+///
+/// With the `serde` feature enabled, it can be serialized and deserialized, for example to report results
+/// as JSON. The number is encoded as num-bigint encodes a `BigUint`, a sequence of 32-bit digits starting
+/// with the least significant.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::{ miller_rabin, PrimalityResult };
+///
+/// let number = BigUint::from(97u32);
+/// let result = PrimalityResult::new(number.clone(), "miller-rabin", miller_rabin(&number));
+/// assert_eq!(result.test, "miller-rabin");
+/// assert!(result.prime);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrimalityResult {
+    /// The number that was tested.
+    pub number: BigUint,
+    /// The name of the test that was run.
+    pub test: String,
+    /// Whether the test reported the number as prime.
+    pub prime: bool,
+}
+
+impl PrimalityResult {
+    /// Creates a result for `number`, reported as `prime` or not by the test named `test`.
+    pub fn new(number: BigUint, test: &str, prime: bool) -> Self {
+        PrimalityResult {
+            number,
+            test: test.into(),
+            prime,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primality::miller_rabin;
+
+    #[test]
+    fn new_result() {
+        let number = BigUint::from(561u32);
+        let result = PrimalityResult::new(number.clone(), "miller-rabin", miller_rabin(&number));
+        assert_eq!(result.number, number);
+        assert_eq!(result.test, "miller-rabin");
+        assert_eq!(result.prime, false);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        // 2^127 - 1 spans several digits of the BigUint encoding
+        let number = (BigUint::from(1u32) << 127u32) - 1u32;
+        let result = PrimalityResult::new(number.clone(), "baillie-psw", true);
+
+        let json = serde_json::to_string(&result).unwrap();
+        let decoded: PrimalityResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, result);
+
+        let small = PrimalityResult::new(BigUint::from(15u32), "fermat", false);
+        let json = serde_json::to_string(&small).unwrap();
+        assert_eq!(json, r#"{"number":[15],"test":"fermat","prime":false}"#);
+        assert_eq!(serde_json::from_str::<PrimalityResult>(&json).unwrap(), small);
+    }
+}
@@ -0,0 +1,183 @@
+use num_bigint::BigUint;
+use num_traits::One;
+use rand::RngCore;
+use crate::operations::{ jacobi, pow_mod };
+use crate::operations::utils::{ isqrt, quick_composite_check, random_base };
+use super::standard::standard;
+
+/// Performs the Solovay-Strassen probabilistic primality test.
+///
+/// For a prime `num` and any base `a` coprime to it, Euler's criterion gives
+/// `a^((num-1)/2) ≡ (a/num) (mod num)`, where `(a/num)` is the Jacobi symbol. Each round draws a random
+/// base from `[2, num - 2]` and checks this congruence. A composite number passes a single round with
+/// probability at most 1/2, so the error bound after `rounds` rounds is `2^-rounds`.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `rounds` - The number of random bases to try.
+///
+/// # Returns
+///
+/// * `true` if `num` passes Euler's criterion for every base.
+/// * `false` if `num` fails it for any base, if `num` is divisible by 2, 3 or 5 (other than those primes
+///   themselves), or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::solovay_strassen;
+///
+/// assert!(solovay_strassen(&BigUint::from(1000000007u32), 20));
+/// assert!(!solovay_strassen(&BigUint::from(1000000011u32), 20));
+/// ```
+#[cfg(feature = "std")]
+pub fn solovay_strassen(num: &BigUint, rounds: usize) -> bool {
+    solovay_strassen_with_rng(num, rounds, &mut rand::thread_rng())
+}
+
+/// Tests primality exactly with `standard` when trial division is cheap enough, and with Solovay-Strassen
+/// otherwise.
+///
+/// Trial division is used when the square root of `num` is at most `trial_bound`, so that no candidate
+/// factor above `trial_bound` is ever tried.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `trial_bound` - A `u64` representing the largest candidate factor trial division may try.
+/// * `rounds` - The number of random bases to try when falling back to Solovay-Strassen.
+/// * `rng` - The random number generator used to draw the bases.
+///
+/// # Returns
+///
+/// * The exact verdict of `standard` if `sqrt(num) <= trial_bound`.
+/// * The verdict of the Solovay-Strassen test otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+/// use large_primes::solovay_strassen_or_exact;
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// assert!(!solovay_strassen_or_exact(&BigUint::from(561u32), 100, 1, &mut rng));
+/// assert!(solovay_strassen_or_exact(&BigUint::from(1000000007u32), 100, 20, &mut rng));
+/// ```
+pub fn solovay_strassen_or_exact(num: &BigUint, trial_bound: u64, rounds: usize, rng: &mut impl RngCore) -> bool {
+    if isqrt(num) <= BigUint::from(trial_bound) {
+        return standard(num);
+    }
+    solovay_strassen_with_rng(num, rounds, rng)
+}
+
+/// Performs the Solovay-Strassen test drawing the bases from the given random number generator.
+fn solovay_strassen_with_rng(num: &BigUint, rounds: usize, rng: &mut impl RngCore) -> bool {
+    if let Some(is_prime) = quick_composite_check(num) {
+        return is_prime;
+    }
+
+    let exponent = (num - BigUint::one()) >> 1u32;
+    let minus_one = num - BigUint::one();
+    (0..rounds).all(|_| {
+        let a = random_base(num, rng);
+        let expected = match jacobi(&a, num) {
+            0 => {
+                // `a` shares a factor with `num`
+                return false;
+            }
+            1 => BigUint::one(),
+            _ => minus_one.clone(),
+        };
+        pow_mod(&a, &exponent, num) == expected
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::Zero;
+
+    use super::*;
+
+    #[test]
+    fn edge_cases() {
+        // Test case 0: False
+        assert_eq!(solovay_strassen(&BigUint::zero(), 10), false);
+
+        // Test case 1: False
+        assert_eq!(solovay_strassen(&BigUint::one(), 10), false);
+
+        // Test case 2: True
+        assert_eq!(solovay_strassen(&BigUint::from(2u32), 10), true);
+
+        // Test case 3: True
+        assert_eq!(solovay_strassen(&BigUint::from(3u32), 10), true);
+
+        // Test case 4: False
+        assert_eq!(solovay_strassen(&BigUint::from(4u32), 10), false);
+
+        // Test case 7: True
+        assert_eq!(solovay_strassen(&BigUint::from(7u32), 10), true);
+    }
+
+    #[test]
+    fn large_primes() {
+        let primes = [
+            // 10-12 digit primes
+            "871603259",
+            "98762051",
+            "1000000007",
+            "123575321",
+            "193818613",
+            "444444443",
+            "999999937",
+            "1000000000039",
+            "9999999929",
+        ];
+
+        for prime in primes {
+            let prime = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+            assert_eq!(solovay_strassen(&prime, 20), true);
+        }
+    }
+
+    #[test]
+    fn carmichael_number() {
+        let carmichaels: Vec<BigUint> = vec![
+            BigUint::parse_bytes(b"561", 10).unwrap(),
+            BigUint::parse_bytes(b"41041", 10).unwrap(),
+            BigUint::parse_bytes(b"825265", 10).unwrap(),
+            BigUint::parse_bytes(b"321197185", 10).unwrap(),
+            BigUint::parse_bytes(b"5394826801", 10).unwrap(),
+            BigUint::parse_bytes(b"232250619601", 10).unwrap(),
+            BigUint::parse_bytes(b"9746347772161", 10).unwrap(),
+            BigUint::parse_bytes(b"1436697831295441", 10).unwrap(),
+            BigUint::parse_bytes(b"60977817398996785", 10).unwrap(),
+            BigUint::parse_bytes(b"7156857700403137441", 10).unwrap()
+        ];
+
+        // Unlike Fermat, Euler's criterion is not fooled by Carmichael numbers
+        for carmichael in carmichaels {
+            assert_eq!(solovay_strassen(&carmichael, 30), false);
+        }
+    }
+
+    #[test]
+    fn exact_fallback() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let mut rng = StdRng::seed_from_u64(28);
+
+        // sqrt(561) < 24, so trial division settles it even with no random rounds
+        assert_eq!(solovay_strassen_or_exact(&BigUint::from(561u32), 24, 0, &mut rng), false);
+        assert_eq!(solovay_strassen_or_exact(&BigUint::from(563u32), 24, 0, &mut rng), true);
+
+        // Beyond the bound, zero rounds accept anything that passes quick_composite_check
+        assert_eq!(solovay_strassen_or_exact(&BigUint::from(41041u32), 24, 0, &mut rng), true);
+        assert_eq!(solovay_strassen_or_exact(&BigUint::from(41041u32), 24, 30, &mut rng), false);
+    }
+}
@@ -1,11 +1,14 @@
+#[cfg(feature = "std")]
+use std::time::{ Duration, Instant };
 use num_bigint::BigUint;
-use num_traits::{ One, Zero };
+use super::generic::{ self, PrimeInt };
 
 /// Performs the standard primality test by checking for prime factors from 2 to the square root of the given number.
 ///
 /// This function checks whether the provided number is prime by testing for prime factors in the range from 2
 /// to the square root of the number (inclusive). It returns `true` if the number has no prime factors in that range,
-/// indicating that it is prime.
+/// indicating that it is prime. After 2 and 3, only candidates of the form `6k ± 1` are tried, since every other
+/// number is divisible by 2 or 3.
 ///
 /// # Arguments
 ///
@@ -30,7 +33,117 @@ use num_traits::{ One, Zero };
 /// assert!(!standard(&non_prime));
 /// ```
 pub fn standard(num: &BigUint) -> bool {
-    // Standard test for prime factors from 2 to sqrt(num)+1
+    generic::standard(num)
+}
+
+/// Trial divides `num` by 2, 3 and every `6k ± 1` up to its square root.
+///
+/// `keep_going` is called before each `6k ± 1` pair, and the search stops without a verdict as soon as it
+/// returns `false`.
+pub(crate) fn wheel_trial_division<T: PrimeInt>(num: &T, mut keep_going: impl FnMut() -> bool) -> Option<bool> {
+    if *num <= T::from(1u32) {
+        return Some(false);
+    }
+    for p in [2u32, 3].map(T::from) {
+        if *num == p {
+            return Some(true);
+        }
+        if num.is_divisible_by(&p) {
+            return Some(false);
+        }
+    }
+
+    let sqrt_num = num.isqrt();
+
+    // Candidates 6k - 1 and 6k + 1, starting from 5 and 7
+    let mut factor = T::from(5u32);
+    while factor <= sqrt_num {
+        if !keep_going() {
+            return None;
+        }
+        if num.is_divisible_by(&factor) || num.is_divisible_by(&factor.add_u32(2)) {
+            return Some(false);
+        }
+        factor = factor.add_u32(6);
+    }
+
+    Some(true)
+}
+
+/// How many `6k ± 1` pairs `standard_within_budget` tries between two clock checks.
+#[cfg(feature = "std")]
+const BUDGET_CHECK_INTERVAL: u64 = 512;
+
+/// Performs the standard primality test, giving up once a wall-clock budget is spent.
+///
+/// The candidate factors are the same as in `standard`. The elapsed time is checked every 1024 candidates,
+/// and the test stops without a verdict as soon as it exceeds `budget`.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+/// * `budget` - The `Duration` the test may run for.
+///
+/// # Returns
+///
+/// * `Some(verdict)` with the same verdict as `standard` if the test finished within the budget.
+/// * `None` if the budget ran out first.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use num_bigint::BigUint;
+/// use large_primes::standard_within_budget;
+///
+/// assert_eq!(standard_within_budget(&BigUint::from(97u32), Duration::from_secs(1)), Some(true));
+///
+/// let mersenne = BigUint::from(2305843009213693951u64);
+/// assert_eq!(standard_within_budget(&mersenne, Duration::from_millis(1)), None);
+/// ```
+#[cfg(feature = "std")]
+pub fn standard_within_budget(num: &BigUint, budget: Duration) -> Option<bool> {
+    let start = Instant::now();
+    let mut tried = 0u64;
+    wheel_trial_division(num, || {
+        tried += 1;
+        !(tried.is_multiple_of(BUDGET_CHECK_INTERVAL) && start.elapsed() > budget)
+    })
+}
+
+/// Performs the standard primality test, splitting the range of candidate factors across threads.
+///
+/// The range from 2 to the square root of `num` is split into one chunk per `rayon` thread. Each thread
+/// scans its chunk for a divisor, and as soon as one is found a shared `AtomicBool` tells the other threads
+/// to stop early. The verdict is identical to `standard`.
+///
+/// This function is only available with the `parallel` feature enabled.
+///
+/// # Arguments
+///
+/// * `num` - A reference to a `BigUint` representing the number to test for primality.
+///
+/// # Returns
+///
+/// * `true` if `num` has no factors from 2 to sqrt(num).
+/// * `false` if `num` has a factor in that range or if `num` is less than or equal to 1.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigUint;
+/// use large_primes::standard_parallel;
+///
+/// assert!(standard_parallel(&BigUint::from(1000000007u32)));
+/// assert!(!standard_parallel(&BigUint::from(1000000011u32)));
+/// ```
+#[cfg(feature = "parallel")]
+pub fn standard_parallel(num: &BigUint) -> bool {
+    use rayon::prelude::*;
+    use std::sync::atomic::{ AtomicBool, Ordering };
+    use num_traits::{ One, Zero };
+    use crate::operations::utils::isqrt;
+
     if *num <= BigUint::one() {
         return false;
     }
@@ -38,21 +151,34 @@ pub fn standard(num: &BigUint) -> bool {
         return true;
     }
 
-    let sqrt_num = num.sqrt() + BigUint::one();
+    // Candidates are 2..=sqrt_num, split into one chunk per thread
+    let start = BigUint::from(2u32);
+    let end = isqrt(num) + BigUint::from(2u32);
+    let chunks = rayon::current_num_threads().max(1) as u32;
+    let span = (&end - &start) / chunks + BigUint::one();
 
-    let mut factor = BigUint::from(2u32);
-    while &factor <= &sqrt_num {
-        if num % &factor == BigUint::zero() {
-            return false;
+    let found = AtomicBool::new(false);
+    (0..chunks).into_par_iter().for_each(|i| {
+        let mut factor = &start + &span * i;
+        let chunk_end = (&factor + &span).min(end.clone());
+        while factor < chunk_end {
+            if found.load(Ordering::Relaxed) {
+                return;
+            }
+            if num % &factor == BigUint::zero() {
+                found.store(true, Ordering::Relaxed);
+                return;
+            }
+            factor += BigUint::one();
         }
-        factor += BigUint::one();
-    }
+    });
 
-    true
+    !found.load(Ordering::Relaxed)
 }
 
 #[cfg(test)]
 mod tests {
+    use num_traits::{ One, Zero };
     use super::*;
 
     #[test]
@@ -109,4 +235,68 @@ mod tests {
             }
         }
     }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_agrees() {
+        for i in 0..1000u32 {
+            let num = BigUint::from(i);
+            assert_eq!(standard_parallel(&num), standard(&num));
+        }
+
+        let primes = [
+            "871603259",
+            "98762051",
+            "1000000007",
+            "123575321",
+            "193818613",
+            "444444443",
+            "999999937",
+            "1000000000039",
+            "9999999929",
+        ];
+
+        for prime in primes {
+            let prime = BigUint::parse_bytes(prime.as_bytes(), 10).unwrap();
+            assert_eq!(standard_parallel(&prime), standard(&prime));
+        }
+
+        let primes = ["914491", "15959", "767857", "14293", "680123", "617237"];
+
+        for i in 0..primes.len() {
+            for j in 0..primes.len() {
+                if i == j {
+                    continue;
+                }
+                let composite =
+                    BigUint::parse_bytes(primes[i].as_bytes(), 10).unwrap() *
+                    BigUint::parse_bytes(primes[j].as_bytes(), 10).unwrap();
+                assert_eq!(standard_parallel(&composite), standard(&composite));
+            }
+        }
+    }
+
+    #[test]
+    fn within_budget() {
+        // A generous budget gives the same verdict as `standard`
+        for n in [0u32, 1, 2, 97, 561, 1000000007] {
+            let n = BigUint::from(n);
+            assert_eq!(standard_within_budget(&n, Duration::from_secs(60)), Some(standard(&n)));
+        }
+
+        // 2^61 - 1 needs over 10^9 trial divisions
+        let mersenne = BigUint::from(2305843009213693951u64);
+        assert_eq!(standard_within_budget(&mersenne, Duration::from_micros(1)), None);
+
+        // A small factor is found before the first clock check, even with no budget
+        assert_eq!(standard_within_budget(&(&mersenne * 3u32), Duration::ZERO), Some(false));
+    }
+
+    #[test]
+    fn wheel_matches_sieve() {
+        let primes = crate::generators::get_max_primes_u64(10000);
+        for n in 0..=10000u64 {
+            assert_eq!(standard(&BigUint::from(n)), primes.binary_search(&n).is_ok());
+        }
+    }
 }
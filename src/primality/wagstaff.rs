@@ -0,0 +1,61 @@
+use num_bigint::BigUint;
+use num_traits::One;
+use crate::operations::pow;
+use super::baillie_psw::baillie_psw;
+
+/// Checks whether the Wagstaff number (2^p + 1) / 3 is prime.
+///
+/// Unlike Mersenne numbers, Wagstaff numbers have no known deterministic test comparable to
+/// Lucas-Lehmer, so the number is built explicitly and tested with `baillie_psw`.
+///
+/// # Arguments
+///
+/// * `p` - A `u64` representing the exponent `p` in the Wagstaff number (2^p + 1) / 3.
+///
+/// # Returns
+///
+/// * `true` if (2^p + 1) / 3 passes the Baillie-PSW test.
+/// * `false` if it is composite, or if `p` is even (where 2^p + 1 is not divisible by 3).
+///
+/// # Examples
+///
+/// ```
+/// use large_primes::is_wagstaff_prime;
+///
+/// // (2^7 + 1) / 3 = 43
+/// assert!(is_wagstaff_prime(7));
+///
+/// // (2^9 + 1) / 3 = 171 = 9 * 19
+/// assert!(!is_wagstaff_prime(9));
+/// ```
+pub fn is_wagstaff_prime(p: u64) -> bool {
+    if p.is_multiple_of(2) {
+        return false;
+    }
+
+    let wagstaff = (pow(&BigUint::from(2u32), &BigUint::from(p)) + BigUint::one()) / BigUint::from(3u32);
+    baillie_psw(&wagstaff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prime_test() {
+        // 3, 11, 43, 683, 2731
+        let exponents = vec![3, 5, 7, 11, 13, 17, 19, 23, 31, 43, 61, 79, 101, 127];
+        for p in exponents {
+            assert!(is_wagstaff_prime(p));
+        }
+    }
+
+    #[test]
+    fn composite_test() {
+        // 1 is not prime, even exponents do not give an integer, and the rest are composite
+        let exponents = vec![1, 2, 4, 9, 15, 21, 25, 27, 29, 37, 41, 47, 53, 59];
+        for p in exponents {
+            assert!(!is_wagstaff_prime(p));
+        }
+    }
+}